@@ -0,0 +1,11 @@
+#!/bin/bash
+
+# Accepts the pending `*.snap.new` files left behind by failing snapshot tests.
+# Pass folders to limit the search, e.g. `./accept-snapshots.sh day-10`.
+
+DIRS=("${@:-.}")
+
+find "${DIRS[@]}" -path '*/snapshots/*.snap.new' -not -path '*/target/*' | while read -r NEW; do
+    mv "$NEW" "${NEW%.new}"
+    echo "accepted ${NEW%.new}"
+done
@@ -0,0 +1,12 @@
+use day_01::{parse_elves, redistribute};
+use std::{env, fs};
+
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    // spread over the same number of elves unless told otherwise
+    let k = match env::args().nth(1) {
+        Some(k) => k.parse().unwrap(),
+        None => parse_elves(&file).count(),
+    };
+    println!("{}", redistribute(&file, k))
+}
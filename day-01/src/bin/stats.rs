@@ -0,0 +1,7 @@
+use day_01::statistics;
+use std::fs;
+
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!("{}", statistics(&file))
+}
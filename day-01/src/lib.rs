@@ -1,18 +1,412 @@
-pub fn process_part1(input: &str) -> String {
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt,
+};
+
+// One entry per elf, holding the calories of each item it carries. Totals are
+// summed as `u64` so large generated inputs do not overflow.
+pub fn parse_elves(input: &str) -> impl Iterator<Item = Vec<u64>> + '_ {
     input
         .split("\n\n")
-        .map(|line| line.lines().map(|x| x.parse::<u32>().unwrap()).sum::<u32>())
-        .max()
-        .unwrap()
-        .to_string()
+        .map(|group| group.lines().map(|x| x.parse::<u64>().unwrap()).collect())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Elf {
+    pub index: usize,
+    pub total: u64,
+    pub rank: usize,
+}
+
+// The `n` elves carrying the most calories, best first. Equal totals are
+// ranked by original position, so the earlier elf always wins a tie.
+pub fn top_elves(input: &str, n: usize) -> Vec<Elf> {
+    // min-heap of the best `n` seen so far, the weakest of them on top
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for (index, items) in parse_elves(input).enumerate() {
+        let total = items.iter().sum::<u64>();
+        heap.push(Reverse((total, Reverse(index))));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .enumerate()
+        .map(|(rank, Reverse((total, Reverse(index))))| Elf {
+            index,
+            total,
+            rank: rank + 1,
+        })
+        .collect()
+}
+
+pub fn process_part1(input: &str) -> String {
+    top_elves(input, 1)[0].total.to_string()
 }
+
 pub fn process_part2(input: &str) -> String {
-    let mut callories = input
-        .split("\n\n")
-        .map(|line| line.lines().map(|x| x.parse::<u32>().unwrap()).sum::<u32>())
-        .collect::<Vec<u32>>();
-    callories.sort_by(|a, b| b.cmp(a));
-    callories.iter().take(3).sum::<u32>().to_string()
+    top_elves(input, 3)
+        .iter()
+        .map(|elf| elf.total)
+        .sum::<u64>()
+        .to_string()
+}
+
+const PERCENTILES: [usize; 6] = [10, 25, 50, 75, 90, 99];
+// Elves further than this many standard deviations from the mean are listed.
+const OUTLIER_Z: f64 = 3.0;
+const HISTOGRAM_BINS: u64 = 10;
+const HISTOGRAM_WIDTH: usize = 50;
+
+pub struct Stats {
+    pub elves: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub percentiles: Vec<(usize, u64)>,
+    // number of items carried -> number of elves carrying that many
+    pub item_counts: BTreeMap<usize, usize>,
+    // (elf index, total, z-score)
+    pub outliers: Vec<(usize, u64, f64)>,
+    totals: Vec<u64>,
+}
+
+pub fn statistics(input: &str) -> Stats {
+    let mut item_counts = BTreeMap::new();
+    let totals = parse_elves(input)
+        .map(|items| {
+            *item_counts.entry(items.len()).or_insert(0) += 1;
+            items.iter().sum::<u64>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut sorted = totals.clone();
+    sorted.sort();
+    let elves = sorted.len();
+
+    let mean = sorted.iter().sum::<u64>() as f64 / elves as f64;
+    let median = if elves % 2 == 0 {
+        (sorted[elves / 2 - 1] + sorted[elves / 2]) as f64 / 2.0
+    } else {
+        sorted[elves / 2] as f64
+    };
+    let variance = sorted
+        .iter()
+        .map(|total| (*total as f64 - mean).powi(2))
+        .sum::<f64>()
+        / elves as f64;
+    let std_dev = variance.sqrt();
+
+    // nearest-rank percentiles, so every value is one an elf actually carries
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|p| (*p, sorted[(p * elves).div_ceil(100).max(1) - 1]))
+        .collect();
+
+    let outliers = totals
+        .iter()
+        .enumerate()
+        .filter_map(|(index, total)| {
+            let z = (*total as f64 - mean) / std_dev;
+            (z.abs() > OUTLIER_Z).then_some((index, *total, z))
+        })
+        .collect();
+
+    Stats {
+        elves,
+        mean,
+        median,
+        std_dev,
+        percentiles,
+        item_counts,
+        outliers,
+        totals: sorted,
+    }
+}
+
+impl Stats {
+    pub fn histogram(&self) -> String {
+        let (min, max) = (self.totals[0], self.totals[self.totals.len() - 1]);
+        // the range+1 distinct totals split into at most HISTOGRAM_BINS bins,
+        // ending with the one that holds the maximum
+        let bin_width = (max - min + 1).div_ceil(HISTOGRAM_BINS);
+        let bins = (max - min) / bin_width + 1;
+
+        let mut counts = vec![0; bins as usize];
+        for total in self.totals.iter() {
+            counts[((total - min) / bin_width) as usize] += 1;
+        }
+        let most = *counts.iter().max().unwrap();
+
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let from = min + i as u64 * bin_width;
+                let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+                format!(
+                    "{:>8} - {:>8} | {:<width$} {}",
+                    from,
+                    (from + bin_width - 1).min(max),
+                    bar,
+                    count,
+                    width = HISTOGRAM_WIDTH
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "elves: {}", self.elves)?;
+        writeln!(f, "mean: {:.2}", self.mean)?;
+        writeln!(f, "median: {:.1}", self.median)?;
+        writeln!(f, "std dev: {:.2}", self.std_dev)?;
+        let percentiles = self
+            .percentiles
+            .iter()
+            .map(|(p, total)| format!("p{} {}", p, total))
+            .collect::<Vec<_>>();
+        writeln!(f, "percentiles: {}", percentiles.join(", "))?;
+        let item_counts = self
+            .item_counts
+            .iter()
+            .map(|(items, elves)| format!("{} items: {}", items, elves))
+            .collect::<Vec<_>>();
+        writeln!(f, "elves by item count: {}", item_counts.join(", "))?;
+        write!(f, "outliers (|z| > {}):", OUTLIER_Z)?;
+        if self.outliers.is_empty() {
+            writeln!(f, " none")?;
+        } else {
+            writeln!(f)?;
+            for (index, total, z) in self.outliers.iter() {
+                writeln!(f, "  elf {}: {} (z = {:.2})", index, total, z)?;
+            }
+        }
+        writeln!(f, "histogram:")?;
+        write!(f, "{}", self.histogram())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snack {
+    pub elf: usize,
+    pub item: usize,
+    pub calories: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Method {
+    // branch and bound over every assignment, optimal unless it runs out of
+    // budget
+    Exact,
+    // Longest Processing Time first: hand the biggest remaining snack to the
+    // lightest elf. Graham's bound keeps it within 4/3 - 1/(3k) of optimal.
+    LongestFirst,
+}
+
+// Inputs with more snacks than this are planned with the heuristic.
+const EXACT_SNACK_LIMIT: usize = 20;
+// The exact search gives up and keeps the best assignment found so far after
+// visiting this many partial assignments, since hard inputs take k^n steps.
+const SEARCH_NODE_LIMIT: usize = 1_000_000;
+
+pub struct Plan {
+    pub method: Method,
+    pub loads: Vec<Vec<Snack>>,
+    pub before_max: u64,
+    pub after_max: u64,
+    // no assignment can do better than this
+    pub lower_bound: u64,
+    // the exact search ran out of budget, so the plan is the best it found
+    // but not proven optimal
+    pub fell_back: bool,
+}
+
+fn longest_first(snacks: &[Snack], k: usize) -> Vec<usize> {
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = (0..k).map(|elf| Reverse((0, elf))).collect();
+    snacks
+        .iter()
+        .map(|snack| {
+            let Reverse((load, elf)) = heap.pop().unwrap();
+            heap.push(Reverse((load + snack.calories, elf)));
+            elf
+        })
+        .collect()
+}
+
+fn max_load(snacks: &[Snack], assignment: &[usize], k: usize) -> u64 {
+    let mut loads = vec![0; k];
+    for (snack, elf) in snacks.iter().zip(assignment) {
+        loads[*elf] += snack.calories;
+    }
+    loads.into_iter().max().unwrap_or(0)
+}
+
+struct Search<'a> {
+    snacks: &'a [Snack],
+    lower_bound: u64,
+    loads: Vec<u64>,
+    assignment: Vec<usize>,
+    best_max: u64,
+    best: Vec<usize>,
+    nodes_left: usize,
+}
+
+impl Search<'_> {
+    // Returns true once the lower bound is reached and nothing can improve,
+    // or once the node budget is spent.
+    fn run(&mut self, i: usize) -> bool {
+        if self.nodes_left == 0 {
+            return true;
+        }
+        self.nodes_left -= 1;
+        if i == self.snacks.len() {
+            self.best_max = *self.loads.iter().max().unwrap();
+            self.best = self.assignment.clone();
+            return self.best_max == self.lower_bound;
+        }
+        let calories = self.snacks[i].calories;
+        for elf in 0..self.loads.len() {
+            // elves with the same load are interchangeable, try only the first
+            if self.loads[..elf].contains(&self.loads[elf])
+                || self.loads[elf] + calories >= self.best_max
+            {
+                continue;
+            }
+            self.loads[elf] += calories;
+            self.assignment.push(elf);
+            let done = self.run(i + 1);
+            self.assignment.pop();
+            self.loads[elf] -= calories;
+            if done {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// Reassigns every snack among `k` elves so the heaviest load is as small as
+// possible. Snacks are handed out largest first, which both the heuristic and
+// the pruning of the exact search rely on.
+pub fn redistribute(input: &str, k: usize) -> Plan {
+    plan(input, k, SEARCH_NODE_LIMIT)
+}
+
+fn plan(input: &str, k: usize, node_limit: usize) -> Plan {
+    assert!(k > 0, "Need at least one elf to carry the snacks");
+    let elves = parse_elves(input).collect::<Vec<_>>();
+    let before_max = elves
+        .iter()
+        .map(|items| items.iter().sum())
+        .max()
+        .unwrap_or(0);
+
+    let mut snacks = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(elf, items)| {
+            items.iter().enumerate().map(move |(item, calories)| Snack {
+                elf,
+                item,
+                calories: *calories,
+            })
+        })
+        .collect::<Vec<_>>();
+    snacks.sort_by_key(|snack| Reverse(snack.calories));
+
+    let total = snacks.iter().map(|snack| snack.calories).sum::<u64>();
+    let largest = snacks.first().map_or(0, |snack| snack.calories);
+    // some elf carries at least ceil(n / k) snacks, so at least that many of
+    // the smallest ones
+    let pigeonhole = snacks
+        .iter()
+        .rev()
+        .take(snacks.len().div_ceil(k))
+        .map(|snack| snack.calories)
+        .sum::<u64>();
+    let lower_bound = total.div_ceil(k as u64).max(largest).max(pigeonhole);
+
+    let greedy = longest_first(&snacks, k);
+    let mut fell_back = false;
+    let (method, assignment) = if snacks.len() <= EXACT_SNACK_LIMIT {
+        let mut search = Search {
+            snacks: &snacks,
+            lower_bound,
+            loads: vec![0; k],
+            assignment: vec![],
+            best_max: max_load(&snacks, &greedy, k),
+            best: greedy.clone(),
+            nodes_left: node_limit,
+        };
+        if search.best_max > lower_bound {
+            search.run(0);
+        }
+        // the search only records assignments that beat the greedy one
+        fell_back = search.nodes_left == 0 && search.best_max > lower_bound;
+        (Method::Exact, search.best)
+    } else {
+        (Method::LongestFirst, greedy)
+    };
+
+    let mut loads = vec![vec![]; k];
+    for (snack, elf) in snacks.iter().zip(assignment.iter()) {
+        loads[*elf].push(*snack);
+    }
+    Plan {
+        method,
+        after_max: max_load(&snacks, &assignment, k),
+        loads,
+        before_max,
+        lower_bound,
+        fell_back,
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.method {
+            Method::Exact if self.fell_back => {
+                writeln!(f, "method: exact, best found, not proven optimal")?;
+                writeln!(f, "exact search gave up after {} steps", SEARCH_NODE_LIMIT)?
+            }
+            Method::Exact => writeln!(f, "method: exact, optimal")?,
+            Method::LongestFirst => {
+                let k = self.loads.len() as f64;
+                writeln!(
+                    f,
+                    "method: longest first, within {:.3}x of optimal",
+                    4.0 / 3.0 - 1.0 / (3.0 * k)
+                )?;
+            }
+        }
+        writeln!(f, "heaviest load before: {}", self.before_max)?;
+        writeln!(
+            f,
+            "heaviest load after: {} (lower bound {})",
+            self.after_max, self.lower_bound
+        )?;
+        for (elf, snacks) in self.loads.iter().enumerate() {
+            let moves = snacks
+                .iter()
+                .map(|snack| format!("{} (elf {} #{})", snack.calories, snack.elf, snack.item))
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "elf {}: {} <- {}",
+                elf,
+                snacks.iter().map(|snack| snack.calories).sum::<u64>(),
+                moves.join(", ")
+            )?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -45,4 +439,136 @@ mod tests {
         let result = process_part2(INPUT);
         assert_eq!(result, "45000");
     }
+
+    #[test]
+    fn top_elves_breaks_ties_by_position() {
+        let result = top_elves("3\n\n5\n\n1\n2\n\n5\n\n4", 3);
+        assert_eq!(
+            result,
+            [
+                Elf {
+                    index: 1,
+                    total: 5,
+                    rank: 1,
+                },
+                Elf {
+                    index: 3,
+                    total: 5,
+                    rank: 2,
+                },
+                Elf {
+                    index: 4,
+                    total: 4,
+                    rank: 3,
+                },
+            ]
+        );
+        assert_eq!(top_elves(INPUT, 0), []);
+        assert_eq!(top_elves(INPUT, 10).len(), 5);
+    }
+
+    #[test]
+    fn stats() {
+        let stats = statistics(INPUT);
+        assert_eq!(stats.elves, 5);
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert_eq!(format!("{:.2}", stats.std_dev), "6985.70");
+        assert_eq!(
+            stats.percentiles,
+            [
+                (10, 4000),
+                (25, 6000),
+                (50, 10000),
+                (75, 11000),
+                (90, 24000),
+                (99, 24000)
+            ]
+        );
+        assert_eq!(stats.item_counts, BTreeMap::from([(1, 2), (2, 1), (3, 2)]));
+        assert!(stats.outliers.is_empty());
+    }
+
+    #[test]
+    fn stats_outliers() {
+        let input = format!("{}\n\n10000", ["100"; 10].join("\n\n"));
+        let stats = statistics(&input);
+        assert_eq!(stats.outliers.len(), 1);
+        assert_eq!(stats.outliers[0].0, 10);
+        assert_eq!(stats.outliers[0].1, 10000);
+    }
+
+    #[test]
+    fn stats_do_not_overflow() {
+        let stats = statistics("4294967295\n4294967295");
+        assert_eq!(stats.mean, 8589934590.0);
+    }
+
+    #[test]
+    fn redistribute_exact() {
+        let plan = redistribute(INPUT, 5);
+        assert_eq!(plan.method, Method::Exact);
+        assert_eq!((plan.before_max, plan.after_max), (24000, 11000));
+
+        let plan = redistribute(INPUT, 3);
+        assert_eq!(plan.lower_bound, 18334);
+        assert_eq!(plan.after_max, 19000);
+        assert_eq!(plan.loads.iter().map(|load| load.len()).sum::<usize>(), 10);
+    }
+
+    #[test]
+    fn redistribute_large() {
+        let input = ["1"; 30].join("\n");
+        let plan = redistribute(&input, 4);
+        assert_eq!(plan.method, Method::LongestFirst);
+        assert_eq!((plan.before_max, plan.after_max), (30, 8));
+    }
+
+    #[test]
+    fn redistribute_above_lower_bound() {
+        // no split of 7 5 4 4 reaches 10, the best is 7 + 4 against 5 + 4
+        let plan = redistribute("7\n5\n4\n4", 2);
+        assert_eq!(plan.method, Method::Exact);
+        assert_eq!((plan.lower_bound, plan.after_max), (10, 11));
+
+        // two of the three snacks share an elf, at least 5 + 5
+        assert_eq!(redistribute("5\n5\n5", 2).lower_bound, 10);
+    }
+
+    #[test]
+    fn redistribute_gives_up_on_hard_inputs() {
+        let input = (0..20u64)
+            .map(|i| (1000 + i * 37 % 101).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let greedy = plan(&input, 6, 0);
+        let plan = plan(&input, 6, 1000);
+        assert_eq!(plan.method, Method::Exact);
+        assert!(plan.fell_back);
+        assert!(plan.to_string().contains("not proven optimal"));
+        assert!(plan.after_max <= greedy.after_max);
+        assert!(plan.after_max >= plan.lower_bound);
+        assert!(!redistribute(INPUT, 5).fell_back);
+    }
+
+    #[test]
+    fn histogram_of_constant_totals() {
+        let histogram = statistics("1\n\n1\n\n1").histogram();
+        assert_eq!(histogram.lines().count(), 1);
+        assert!(histogram.starts_with("       1 -        1 |"));
+        assert!(histogram.ends_with(" 3"));
+
+        // eleven distinct totals still fit in ten bins, the last holding 10
+        let input = (0..=10)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let histogram = statistics(&input).histogram();
+        assert_eq!(histogram.lines().count(), 6);
+        assert!(histogram
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("      10 -       10 |"));
+    }
 }
@@ -0,0 +1,18 @@
+use day_02::{total_score, AsMove, AsOutcome, Game, Table};
+use std::{env, fs};
+
+// Plays ./input.txt under the game definition file given as the first
+// argument, and optionally under a code table such as "X=Rock Y=draw Z=win".
+fn main() {
+    let mut args = env::args().skip(1);
+    let definition =
+        fs::read_to_string(args.next().expect("usage: custom <game file> [table]")).unwrap();
+    let game: Game = definition.parse().unwrap();
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!("{}", total_score(&file, &game, &AsMove).unwrap());
+    println!("{}", total_score(&file, &game, &AsOutcome).unwrap());
+    if let Some(table) = args.next() {
+        let table = Table::parse(&table, &game).unwrap();
+        println!("{}", total_score(&file, &game, &table).unwrap());
+    }
+}
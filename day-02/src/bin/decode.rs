@@ -0,0 +1,10 @@
+use day_02::{decode_guide, render_decodings, Game};
+use std::fs;
+
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!(
+        "{}",
+        render_decodings(&decode_guide(&file, &Game::default()).unwrap())
+    )
+}
@@ -0,0 +1,13 @@
+use day_02::{export, Game};
+use std::{env, fs};
+
+// Writes every round of ./input.txt as CSV, or as TSV with --tsv.
+fn main() {
+    let separator = match env::args().nth(1).as_deref() {
+        Some("--tsv") => '\t',
+        None | Some("--csv") => ',',
+        Some(other) => panic!("usage: export [--csv|--tsv], got {}", other),
+    };
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!("{}", export(&file, &Game::default(), separator).unwrap());
+}
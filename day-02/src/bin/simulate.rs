@@ -0,0 +1,60 @@
+use day_02::{render_standings, simulate, strategies, tournament, Game};
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: simulate [--log <n> | --tournament [rounds]]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+// Plays every strategy against the opponent moves of ./input.txt.
+//   simulate                      scores and win/draw/loss counts
+//   simulate --log <n>            round-by-round log of the n-th strategy
+//   simulate --tournament [rounds] round-robin between the strategies
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let file = fs::read_to_string("./input.txt").unwrap();
+    let game = Game::default();
+    let strategies = strategies(&file, &game).unwrap();
+
+    match args.first().map(String::as_str) {
+        Some("--tournament") => {
+            let rounds = match args.get(1) {
+                Some(rounds) => rounds.parse().unwrap_or_else(|_| usage()),
+                None => file.lines().count(),
+            };
+            println!(
+                "{}",
+                render_standings(&tournament(&game, &strategies, rounds))
+            );
+        }
+        Some("--log") => {
+            let n = args
+                .get(1)
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or_else(|| usage());
+            let Some(strategy) = n.checked_sub(1).and_then(|i| strategies.get(i)) else {
+                eprintln!(
+                    "Strategies are numbered 1 to {}, got {}",
+                    strategies.len(),
+                    n
+                );
+                usage()
+            };
+            println!(
+                "{}",
+                simulate(&file, &game, strategy.as_ref())
+                    .unwrap()
+                    .log(&game)
+            );
+        }
+        None => {
+            for (i, strategy) in strategies.iter().enumerate() {
+                let report = simulate(&file, &game, strategy.as_ref()).unwrap();
+                println!("{:>2}. {}", i + 1, report.summary());
+            }
+        }
+        Some(_) => usage(),
+    }
+}
@@ -1,145 +1,779 @@
 use std::str::FromStr;
 
-#[derive(Clone, Copy)]
-enum Move {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+// Index of a shape in the order it was defined in its `Game`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+#[derive(Debug, Clone)]
+struct Shape {
+    name: String,
+    value: u32,
+    opponent_code: String,
+    own_code: String,
 }
 
-struct Round {
-    opponent: Move,
-    me: Move,
+// A cyclic hand game. Shapes are listed in cycle order and every shape beats
+// the (n - 1) / 2 shapes right before it, which for an odd number of shapes
+// makes each pair of different shapes have exactly one winner.
+#[derive(Debug, Clone)]
+pub struct Game {
+    shapes: Vec<Shape>,
+    // points for a loss, a draw and a win
+    points: [u32; 3],
+    // second column codes meaning lose, draw and win
+    outcome_codes: [String; 3],
 }
 
-impl FromStr for Round {
+// One line per shape: `<name> <value> <opponent code> <own code>`, followed by
+// the points for a loss, draw and win and the codes that ask for them.
+pub const CLASSIC: &str = "Rock 1 A X
+Paper 2 B Y
+Scissors 3 C Z
+points 0 3 6
+outcomes X Y Z";
+
+pub const ROCK_PAPER_SCISSORS_LIZARD_SPOCK: &str = "Rock 1 A V
+Spock 5 B W
+Paper 2 C X
+Lizard 4 D Y
+Scissors 3 E Z
+points 0 3 6
+outcomes X Y Z";
+
+impl FromStr for Game {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 3 {
+        let mut shapes = vec![];
+        let mut points = None;
+        let mut outcome_codes = None;
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["points", lose, draw, win] => {
+                    let parse = |v: &str| {
+                        v.parse::<u32>()
+                            .map_err(|_| format!("Invalid points, got {}", v))
+                    };
+                    points = Some([parse(lose)?, parse(draw)?, parse(win)?]);
+                }
+                ["outcomes", lose, draw, win] => {
+                    outcome_codes = Some([lose, draw, win].map(|code| code.to_string()));
+                }
+                [name, value, opponent_code, own_code] => shapes.push(Shape {
+                    name: name.to_string(),
+                    value: value
+                        .parse()
+                        .map_err(|_| format!("Invalid value for {}, got {}", name, value))?,
+                    opponent_code: opponent_code.to_string(),
+                    own_code: own_code.to_string(),
+                }),
+                _ => return Err(format!("Invalid game definition line, got {}", line)),
+            }
+        }
+
+        if shapes.len() < 3 || shapes.len() % 2 == 0 {
             return Err(format!(
-                "Length of the input must be equal to 3, got {}",
-                s.len()
+                "A cyclic game needs an odd number of at least 3 shapes, got {}",
+                shapes.len()
             ));
         }
-        let parts: Vec<&str> = s.split(" ").collect();
-        let opponent = match parts[0] {
-            "A" => Move::Rock,
-            "B" => Move::Paper,
-            "C" => Move::Scissors,
-            v => {
-                return Err(format!("Invalid argument, got {}", v));
-            }
-        };
-        let me = match parts[1] {
-            "X" => Move::Rock,
-            "Y" => Move::Paper,
-            "Z" => Move::Scissors,
-            v => {
-                return Err(format!("Invalid argument, got {}", v));
+        for (i, shape) in shapes.iter().enumerate() {
+            if shapes[..i].iter().any(|other| {
+                other.opponent_code == shape.opponent_code || other.own_code == shape.own_code
+            }) {
+                return Err(format!("Duplicate code for {}", shape.name));
             }
-        };
-        Ok(Self {
-            opponent: opponent,
-            me: me,
+        }
+        Ok(Game {
+            shapes,
+            points: points.ok_or("Missing points line")?,
+            outcome_codes: outcome_codes.ok_or("Missing outcomes line")?,
         })
     }
 }
 
-impl Round {
-    fn score(&self) -> u32 {
-        self.me as u32
-            + match (self.opponent, self.me) {
-                (Move::Rock, Move::Rock) => 3,
-                (Move::Rock, Move::Paper) => 6,
-                (Move::Rock, Move::Scissors) => 0,
-                (Move::Paper, Move::Rock) => 0,
-                (Move::Paper, Move::Paper) => 3,
-                (Move::Paper, Move::Scissors) => 6,
-                (Move::Scissors, Move::Rock) => 6,
-                (Move::Scissors, Move::Paper) => 0,
-                (Move::Scissors, Move::Scissors) => 3,
-            }
+impl Default for Game {
+    fn default() -> Self {
+        CLASSIC.parse().unwrap()
     }
 }
 
-struct PredictRound {
-    opponent: Move,
-    me: Move,
+impl Game {
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.shapes.len()).map(Move)
+    }
+
+    pub fn name(&self, mv: Move) -> &str {
+        &self.shapes[mv.0].name
+    }
+
+    pub fn value(&self, mv: Move) -> u32 {
+        self.shapes[mv.0].value
+    }
+
+    pub fn beats(&self, a: Move, b: Move) -> bool {
+        let n = self.shapes.len();
+        (1..=n / 2).contains(&((a.0 + n - b.0) % n))
+    }
+
+    pub fn outcome(&self, me: Move, opponent: Move) -> Outcome {
+        if me == opponent {
+            Outcome::Draw
+        } else if self.beats(me, opponent) {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    pub fn outcome_points(&self, outcome: Outcome) -> u32 {
+        self.points[outcome as usize]
+    }
+
+    pub fn score(&self, opponent: Move, me: Move) -> u32 {
+        self.value(me) + self.outcome_points(self.outcome(me, opponent))
+    }
+
+    pub fn opponent_move(&self, code: &str) -> Result<Move, String> {
+        self.moves()
+            .find(|mv| self.shapes[mv.0].opponent_code == code)
+            .ok_or_else(|| format!("Invalid argument, got {}", code))
+    }
+
+    pub fn own_move(&self, code: &str) -> Result<Move, String> {
+        self.moves()
+            .find(|mv| self.shapes[mv.0].own_code == code)
+            .ok_or_else(|| format!("Invalid argument, got {}", code))
+    }
+
+    pub fn wanted_outcome(&self, code: &str) -> Result<Outcome, String> {
+        match self.outcome_codes.iter().position(|c| c == code) {
+            Some(0) => Ok(Outcome::Lose),
+            Some(1) => Ok(Outcome::Draw),
+            Some(_) => Ok(Outcome::Win),
+            None => Err(format!("Invalid argument, got {}", code)),
+        }
+    }
+
+    pub fn move_for(&self, opponent: Move, outcome: Outcome) -> Move {
+        self.moves()
+            .find(|mv| self.outcome(*mv, opponent) == outcome)
+            .unwrap()
+    }
 }
 
-impl FromStr for PredictRound {
-    type Err = String;
+// Decides what the second column of a guide line asks us to play.
+pub trait Interpretation {
+    fn own_move(&self, game: &Game, opponent: Move, code: &str) -> Result<Move, String>;
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 3 {
-            return Err(format!(
-                "Length of the input must be equal to 3, got {}",
-                s.len()
-            ));
+// The second column names our shape (part 1).
+pub struct AsMove;
+
+impl Interpretation for AsMove {
+    fn own_move(&self, game: &Game, _opponent: Move, code: &str) -> Result<Move, String> {
+        game.own_move(code)
+    }
+}
+
+// The second column names the outcome we need (part 2).
+pub struct AsOutcome;
+
+impl Interpretation for AsOutcome {
+    fn own_move(&self, game: &Game, opponent: Move, code: &str) -> Result<Move, String> {
+        Ok(game.move_for(opponent, game.wanted_outcome(code)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meaning {
+    Play(Move),
+    Reach(Outcome),
+}
+
+// A user-supplied code table such as `X=Rock Y=draw Z=Paper`, where each code
+// maps to either a shape of the game or one of lose, draw and win.
+#[derive(Debug, Clone)]
+pub struct Table {
+    entries: Vec<(String, Meaning)>,
+}
+
+impl Table {
+    pub fn new(entries: Vec<(String, Meaning)>) -> Self {
+        Table { entries }
+    }
+
+    pub fn parse(s: &str, game: &Game) -> Result<Self, String> {
+        let entries = s
+            .split_whitespace()
+            .map(|entry| {
+                let (code, meaning) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected <code>=<meaning>, got {}", entry))?;
+                let meaning = match meaning {
+                    "lose" => Meaning::Reach(Outcome::Lose),
+                    "draw" => Meaning::Reach(Outcome::Draw),
+                    "win" => Meaning::Reach(Outcome::Win),
+                    name => Meaning::Play(
+                        game.moves()
+                            .find(|mv| game.name(*mv) == name)
+                            .ok_or_else(|| format!("Unknown shape or outcome, got {}", name))?,
+                    ),
+                };
+                Ok((code.to_string(), meaning))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Table { entries })
+    }
+}
+
+impl Interpretation for Table {
+    fn own_move(&self, game: &Game, opponent: Move, code: &str) -> Result<Move, String> {
+        match self.entries.iter().find(|(c, _)| c == code) {
+            Some((_, Meaning::Play(mv))) => Ok(*mv),
+            Some((_, Meaning::Reach(outcome))) => Ok(game.move_for(opponent, *outcome)),
+            None => Err(format!("Invalid argument, got {}", code)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Move,
+    pub me: Move,
+}
+
+impl Round {
+    pub fn parse(
+        s: &str,
+        game: &Game,
+        interpretation: &impl Interpretation,
+    ) -> Result<Self, String> {
+        let (opponent, code) = s
+            .split_once(' ')
+            .ok_or_else(|| format!("Expected two codes separated by a space, got {}", s))?;
+        let opponent = game.opponent_move(opponent)?;
+        Ok(Self {
+            opponent,
+            me: interpretation.own_move(game, opponent, code)?,
+        })
+    }
+
+    pub fn score(&self, game: &Game) -> u32 {
+        game.score(self.opponent, self.me)
+    }
+}
+
+pub fn total_score(
+    input: &str,
+    game: &Game,
+    interpretation: &impl Interpretation,
+) -> Result<u32, String> {
+    input
+        .lines()
+        .map(|line| Round::parse(line, game, interpretation).map(|round| round.score(game)))
+        .sum()
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut result = vec![];
+    for rest in permutations(n - 1) {
+        for i in 0..n {
+            let mut perm = rest.clone();
+            perm.insert(i, n - 1);
+            result.push(perm);
         }
-        let parts: Vec<&str> = s.split(" ").collect();
-        let opponent = match parts[0] {
-            "A" => Move::Rock,
-            "B" => Move::Paper,
-            "C" => Move::Scissors,
-            v => {
-                return Err(format!("Invalid argument, got {}", v));
+    }
+    result.sort();
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Moves,
+    Outcomes,
+}
+
+// One way to read the second column of the guide: `meanings[i]` is what the
+// i-th code stands for, and `score` is what following the guide earns that way.
+#[derive(Debug)]
+pub struct Decoding {
+    pub column: Column,
+    pub codes: Vec<String>,
+    pub meanings: Vec<String>,
+    pub score: u32,
+}
+
+// Scores the guide under every assignment of the second column codes, both as
+// shapes to play and as outcomes to reach.
+pub fn decode_guide(input: &str, game: &Game) -> Result<Vec<Decoding>, String> {
+    let own_codes = game
+        .shapes
+        .iter()
+        .map(|shape| shape.own_code.clone())
+        .collect::<Vec<_>>();
+    let outcomes = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+
+    let mut decodings = vec![];
+    for perm in permutations(own_codes.len()) {
+        let table = Table::new(
+            own_codes
+                .iter()
+                .zip(perm.iter())
+                .map(|(code, i)| (code.clone(), Meaning::Play(Move(*i))))
+                .collect(),
+        );
+        decodings.push(Decoding {
+            column: Column::Moves,
+            codes: own_codes.clone(),
+            meanings: perm
+                .iter()
+                .map(|i| game.name(Move(*i)).to_string())
+                .collect(),
+            score: total_score(input, game, &table)?,
+        });
+    }
+    for perm in permutations(outcomes.len()) {
+        let table = Table::new(
+            game.outcome_codes
+                .iter()
+                .zip(perm.iter())
+                .map(|(code, i)| (code.clone(), Meaning::Reach(outcomes[*i])))
+                .collect(),
+        );
+        decodings.push(Decoding {
+            column: Column::Outcomes,
+            codes: game.outcome_codes.to_vec(),
+            meanings: perm
+                .iter()
+                .map(|i| format!("{:?}", outcomes[*i]).to_lowercase())
+                .collect(),
+            score: total_score(input, game, &table)?,
+        });
+    }
+    Ok(decodings)
+}
+
+pub fn render_decodings(decodings: &[Decoding]) -> String {
+    let best = decodings.iter().map(|d| d.score).max().unwrap_or(0);
+    let worst = decodings.iter().map(|d| d.score).min().unwrap_or(0);
+
+    decodings
+        .iter()
+        .enumerate()
+        .map(|(i, decoding)| {
+            let mapping = decoding
+                .codes
+                .iter()
+                .zip(decoding.meanings.iter())
+                .map(|(code, meaning)| format!("{}={}", code, meaning))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut flags = vec![];
+            if decoding.score == best {
+                flags.push("best".to_string());
             }
-        };
+            if decoding.score == worst {
+                flags.push("worst".to_string());
+            }
+            let tied = decodings
+                .iter()
+                .enumerate()
+                .filter(|(j, other)| *j != i && other.score == decoding.score)
+                .map(|(j, _)| (j + 1).to_string())
+                .collect::<Vec<_>>();
+            if !tied.is_empty() {
+                flags.push(format!("tied with {}", tied.join(", ")));
+            }
+            let column = match decoding.column {
+                Column::Moves => "moves",
+                Column::Outcomes => "outcomes",
+            };
+            format!(
+                "{:>3} {:<8} {:<40} {:>8} {}",
+                i + 1,
+                column,
+                mapping,
+                decoding.score,
+                flags.join(", ")
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Picks our move for round `round` given the opponent's moves so far. Strategies
+// are stateless so the same one can play several matches.
+pub trait Strategy {
+    fn name(&self, game: &Game) -> String;
+    fn choose(&self, game: &Game, round: usize, history: &[Move]) -> Move;
+}
+
+pub struct Fixed(pub Move);
+
+impl Strategy for Fixed {
+    fn name(&self, game: &Game) -> String {
+        format!("always {}", game.name(self.0))
+    }
+
+    fn choose(&self, _game: &Game, _round: usize, _history: &[Move]) -> Move {
+        self.0
+    }
+}
+
+// Ties go to the shape defined first, so an empty history predicts it too.
+fn most_common(game: &Game, moves: impl Iterator<Item = Move>) -> Move {
+    let mut counts = vec![0; game.shapes.len()];
+    for mv in moves {
+        counts[mv.0] += 1;
+    }
+    game.moves()
+        .max_by_key(|mv| (counts[mv.0], std::cmp::Reverse(mv.0)))
+        .unwrap()
+}
+
+// Beats whatever the opponent has played most often.
+pub struct Frequency;
+
+impl Strategy for Frequency {
+    fn name(&self, _game: &Game) -> String {
+        "frequency".to_string()
+    }
+
+    fn choose(&self, game: &Game, _round: usize, history: &[Move]) -> Move {
+        game.move_for(most_common(game, history.iter().copied()), Outcome::Win)
+    }
+}
 
-        let me = match parts[1] {
-            "X" => match opponent {
-                Move::Rock => Move::Scissors,
-                Move::Paper => Move::Rock,
-                Move::Scissors => Move::Paper,
-            },
-            "Y" => opponent,
-            "Z" => match opponent {
-                Move::Rock => Move::Paper,
-                Move::Paper => Move::Scissors,
-                Move::Scissors => Move::Rock,
-            },
-            v => {
-                return Err(format!("Invalid argument, got {}", v));
+// Beats the opponent's most frequent follow-up to their last move, falling
+// back to plain frequency before that move has been followed by anything.
+pub struct Markov;
+
+impl Strategy for Markov {
+    fn name(&self, _game: &Game) -> String {
+        "markov".to_string()
+    }
+
+    fn choose(&self, game: &Game, _round: usize, history: &[Move]) -> Move {
+        let prediction = match history.last() {
+            Some(last) => {
+                let mut next = history
+                    .windows(2)
+                    .filter(|pair| pair[0] == *last)
+                    .map(|pair| pair[1])
+                    .peekable();
+                if next.peek().is_some() {
+                    most_common(game, next)
+                } else {
+                    most_common(game, history.iter().copied())
+                }
             }
+            None => most_common(game, history.iter().copied()),
         };
-        Ok(Self {
-            opponent: opponent,
-            me: me,
+        game.move_for(prediction, Outcome::Win)
+    }
+}
+
+// Plays the moves the guide asks for under some interpretation, starting over
+// once a longer match runs past the end of the guide.
+pub struct FollowGuide {
+    name: String,
+    moves: Vec<Move>,
+}
+
+impl FollowGuide {
+    pub fn new(
+        name: &str,
+        input: &str,
+        game: &Game,
+        interpretation: &impl Interpretation,
+    ) -> Result<Self, String> {
+        let moves = input
+            .lines()
+            .map(|line| Round::parse(line, game, interpretation).map(|round| round.me))
+            .collect::<Result<Vec<_>, String>>()?;
+        if moves.is_empty() {
+            return Err("Cannot follow an empty guide".to_string());
+        }
+        Ok(FollowGuide {
+            name: name.to_string(),
+            moves,
         })
     }
 }
 
-impl PredictRound {
-    fn score(&self) -> u32 {
-        self.me as u32
-            + match (self.opponent, self.me) {
-                (Move::Rock, Move::Rock) => 3,
-                (Move::Rock, Move::Paper) => 6,
-                (Move::Rock, Move::Scissors) => 0,
-                (Move::Paper, Move::Rock) => 0,
-                (Move::Paper, Move::Paper) => 3,
-                (Move::Paper, Move::Scissors) => 6,
-                (Move::Scissors, Move::Rock) => 6,
-                (Move::Scissors, Move::Paper) => 0,
-                (Move::Scissors, Move::Scissors) => 3,
+impl Strategy for FollowGuide {
+    fn name(&self, _game: &Game) -> String {
+        self.name.clone()
+    }
+
+    fn choose(&self, _game: &Game, round: usize, _history: &[Move]) -> Move {
+        self.moves[round % self.moves.len()]
+    }
+}
+
+pub fn strategies(input: &str, game: &Game) -> Result<Vec<Box<dyn Strategy>>, String> {
+    let mut strategies: Vec<Box<dyn Strategy>> = game
+        .moves()
+        .map(|mv| Box::new(Fixed(mv)) as Box<dyn Strategy>)
+        .collect();
+    strategies.push(Box::new(Frequency));
+    strategies.push(Box::new(Markov));
+    strategies.push(Box::new(FollowGuide::new(
+        "guide as moves",
+        input,
+        game,
+        &AsMove,
+    )?));
+    strategies.push(Box::new(FollowGuide::new(
+        "guide as outcomes",
+        input,
+        game,
+        &AsOutcome,
+    )?));
+    Ok(strategies)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    pub opponent: Move,
+    pub me: Move,
+    pub outcome: Outcome,
+    pub score: u32,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub strategy: String,
+    pub turns: Vec<Turn>,
+}
+
+impl Report {
+    pub fn score(&self) -> u32 {
+        self.turns.iter().map(|turn| turn.score).sum()
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.turns
+            .iter()
+            .filter(|turn| turn.outcome == outcome)
+            .count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{:<20} {:>8}  W {:>5}  D {:>5}  L {:>5}",
+            self.strategy,
+            self.score(),
+            self.count(Outcome::Win),
+            self.count(Outcome::Draw),
+            self.count(Outcome::Lose)
+        )
+    }
+
+    pub fn log(&self, game: &Game) -> String {
+        let mut total = 0;
+        self.turns
+            .iter()
+            .enumerate()
+            .map(|(i, turn)| {
+                total += turn.score;
+                format!(
+                    "{:>5} {:<10} {:<10} {:<5} {:>3} {:>8}",
+                    i + 1,
+                    game.name(turn.opponent),
+                    game.name(turn.me),
+                    format!("{:?}", turn.outcome).to_lowercase(),
+                    turn.score,
+                    total
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn play(game: &Game, opponent: Move, me: Move) -> Turn {
+    Turn {
+        opponent,
+        me,
+        outcome: game.outcome(me, opponent),
+        score: game.score(opponent, me),
+    }
+}
+
+// Replays the opponent column of the guide against a strategy, which only
+// ever sees the opponent moves of earlier rounds.
+pub fn simulate(input: &str, game: &Game, strategy: &dyn Strategy) -> Result<Report, String> {
+    let opponent = input
+        .lines()
+        .map(|line| {
+            let (code, _) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("Expected two codes separated by a space, got {}", line))?;
+            game.opponent_move(code)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let turns = opponent
+        .iter()
+        .enumerate()
+        .map(|(round, mv)| play(game, *mv, strategy.choose(game, round, &opponent[..round])))
+        .collect();
+    Ok(Report {
+        strategy: strategy.name(game),
+        turns,
+    })
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub strategy: String,
+    pub score: u32,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+// Every strategy plays every other one for `rounds` rounds, each seeing the
+// other's earlier moves. Standings are sorted by total score.
+pub fn tournament(game: &Game, strategies: &[Box<dyn Strategy>], rounds: usize) -> Vec<Standing> {
+    let mut standings = strategies
+        .iter()
+        .map(|strategy| Standing {
+            strategy: strategy.name(game),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    for a in 0..strategies.len() {
+        for b in a + 1..strategies.len() {
+            let (mut moves_a, mut moves_b) = (vec![], vec![]);
+            for round in 0..rounds {
+                let move_a = strategies[a].choose(game, round, &moves_b);
+                let move_b = strategies[b].choose(game, round, &moves_a);
+                moves_a.push(move_a);
+                moves_b.push(move_b);
+                for (standing, turn) in [
+                    (a, play(game, move_b, move_a)),
+                    (b, play(game, move_a, move_b)),
+                ] {
+                    let standing = &mut standings[standing];
+                    standing.score += turn.score;
+                    match turn.outcome {
+                        Outcome::Win => standing.wins += 1,
+                        Outcome::Draw => standing.draws += 1,
+                        Outcome::Lose => standing.losses += 1,
+                    }
+                }
             }
+        }
     }
+    standings.sort_by(|a, b| b.score.cmp(&a.score).then(a.strategy.cmp(&b.strategy)));
+    standings
+}
+
+pub fn render_standings(standings: &[Standing]) -> String {
+    standings
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            format!(
+                "{:>2}. {:<20} {:>8}  W {:>5}  D {:>5}  L {:>5}",
+                i + 1,
+                s.strategy,
+                s.score,
+                s.wins,
+                s.draws,
+                s.losses
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn field(value: &str, separator: char) -> String {
+    if value.contains(separator) || value.contains('"') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// One row per guide line with the round decoded and scored under both the
+// part 1 and part 2 interpretations, separated by `separator` (',' or '\t').
+pub fn export(input: &str, game: &Game, separator: char) -> Result<String, String> {
+    let mut header = vec!["line", "opponent code", "own code", "opponent"]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    for part in ["part 1", "part 2"] {
+        for column in ["move", "outcome", "shape points", "outcome points", "total"] {
+            header.push(format!("{} {}", part, column));
+        }
+    }
+    let mut rows = vec![header];
+
+    let mut totals = [0, 0];
+    for (i, line) in input.lines().enumerate() {
+        let (opponent_code, own_code) = line
+            .split_once(' ')
+            .ok_or_else(|| format!("Expected two codes separated by a space, got {}", line))?;
+        let rounds = [
+            Round::parse(line, game, &AsMove)?,
+            Round::parse(line, game, &AsOutcome)?,
+        ];
+        let mut row = vec![
+            (i + 1).to_string(),
+            opponent_code.to_string(),
+            own_code.to_string(),
+            game.name(rounds[0].opponent).to_string(),
+        ];
+        for (round, total) in rounds.iter().zip(totals.iter_mut()) {
+            let outcome = game.outcome(round.me, round.opponent);
+            *total += round.score(game);
+            row.extend([
+                game.name(round.me).to_string(),
+                format!("{:?}", outcome).to_lowercase(),
+                game.value(round.me).to_string(),
+                game.outcome_points(outcome).to_string(),
+                total.to_string(),
+            ]);
+        }
+        rows.push(row);
+    }
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| field(value, separator))
+                .collect::<Vec<_>>()
+                .join(&separator.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
 }
 
 pub fn process_part1(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| line.parse::<Round>().unwrap().score())
-        .sum::<u32>()
+    total_score(input, &Game::default(), &AsMove)
+        .unwrap()
         .to_string()
 }
 pub fn process_part2(input: &str) -> String {
-    input
-        .lines()
-        .map(|line| line.parse::<PredictRound>().unwrap().score())
-        .sum::<u32>()
+    total_score(input, &Game::default(), &AsOutcome)
+        .unwrap()
         .to_string()
 }
 
@@ -162,4 +796,140 @@ C Z";
         let result = process_part2(INPUT);
         assert_eq!(result, "12");
     }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock() {
+        let game: Game = ROCK_PAPER_SCISSORS_LIZARD_SPOCK.parse().unwrap();
+        let mv = |name: &str| game.moves().find(|mv| game.name(*mv) == name).unwrap();
+        for (winner, loser) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert!(game.beats(mv(winner), mv(loser)));
+            assert!(!game.beats(mv(loser), mv(winner)));
+        }
+
+        // my Spock beats Rock, my Paper loses to Lizard, my Rock loses to Spock
+        assert_eq!(total_score("A W\nD X\nB V", &game, &AsMove), Ok(11 + 2 + 1));
+    }
+
+    #[test]
+    fn invalid_games() {
+        assert!("Rock 1 A X\nPaper 2 B Y\npoints 0 3 6\noutcomes X Y Z"
+            .parse::<Game>()
+            .is_err());
+        assert!(CLASSIC.replace("C Z", "C Y").parse::<Game>().is_err());
+        assert!(CLASSIC.replace("points 0 3 6", "").parse::<Game>().is_err());
+    }
+
+    #[test]
+    fn decoder() {
+        let decodings = decode_guide(INPUT, &Game::default()).unwrap();
+        assert_eq!(decodings.len(), 12);
+
+        let moves = &decodings[0];
+        assert_eq!(moves.column, Column::Moves);
+        assert_eq!(moves.meanings, ["Rock", "Paper", "Scissors"]);
+        assert_eq!(moves.score, 15);
+
+        let outcomes = &decodings[6];
+        assert_eq!(outcomes.column, Column::Outcomes);
+        assert_eq!(outcomes.meanings, ["lose", "draw", "win"]);
+        assert_eq!(outcomes.score, 12);
+
+        let report = render_decodings(&decodings);
+        assert_eq!(report.lines().filter(|l| l.contains("best")).count(), 1);
+        assert!(report.lines().next().unwrap().contains("tied with"));
+    }
+
+    #[test]
+    fn mapping_tables() {
+        let game = Game::default();
+        let table = Table::parse("X=Scissors Y=win Z=draw", &game).unwrap();
+        // Paper to beat Rock, Scissors against Paper, Scissors to draw Scissors
+        assert_eq!(total_score(INPUT, &game, &table), Ok(8 + 9 + 6));
+        assert!(Table::parse("X=Stone", &game).is_err());
+        assert!(total_score("A W", &game, &table).is_err());
+    }
+
+    #[test]
+    fn simulator() {
+        let game = Game::default();
+        let guide = simulate(
+            INPUT,
+            &game,
+            &FollowGuide::new("guide", INPUT, &game, &AsMove).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(guide.score(), 15);
+        assert_eq!(
+            [Outcome::Win, Outcome::Draw, Outcome::Lose].map(|o| guide.count(o)),
+            [1, 1, 1]
+        );
+        assert_eq!(
+            guide
+                .log(&game)
+                .lines()
+                .last()
+                .unwrap()
+                .split_whitespace()
+                .last(),
+            Some("15")
+        );
+
+        // the opponent alternates Rock and Paper, which the Markov model learns
+        let input = "A X\nB X\nA X\nB X\nA X\nB X";
+        let markov = simulate(input, &game, &Markov).unwrap();
+        assert!(markov.turns[3..]
+            .iter()
+            .all(|turn| turn.outcome == Outcome::Win));
+        let frequency = simulate(input, &game, &Frequency).unwrap();
+        assert!(frequency.count(Outcome::Win) < markov.count(Outcome::Win));
+    }
+
+    #[test]
+    fn round_robin() {
+        let game = Game::default();
+        let strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(Fixed(Move(0))),
+            Box::new(Fixed(Move(1))),
+            Box::new(Frequency),
+        ];
+        let standings = tournament(&game, &strategies, 10);
+        // Frequency opens with Paper, so it only misses the first round
+        // against Paper, which in turn beats Rock every round
+        assert_eq!(standings[0].strategy, "frequency");
+        assert_eq!(standings[0].wins, 19);
+        assert_eq!(standings[2].strategy, "always Rock");
+        assert_eq!(standings[2].wins, 0);
+        assert_eq!(
+            standings.iter().map(|s| s.wins).sum::<usize>(),
+            standings.iter().map(|s| s.losses).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn export_rows() {
+        let csv = export(INPUT, &Game::default(), ',').unwrap();
+        let rows = csv.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[0].starts_with("line,opponent code,own code,opponent,part 1 move"));
+        assert_eq!(rows[1], "1,A,Y,Rock,Paper,win,2,6,8,Rock,draw,1,3,4");
+        assert!(rows[3].ends_with(",15,Rock,win,1,6,12"));
+
+        let tsv = export(INPUT, &Game::default(), '\t').unwrap();
+        assert_eq!(
+            tsv.lines().nth(2),
+            Some("2\tB\tX\tPaper\tRock\tlose\t1\t0\t9\tRock\tlose\t1\t0\t5")
+        );
+        assert_eq!(field("a,b", ','), "\"a,b\"");
+    }
 }
@@ -4,4 +4,4 @@ use std::fs;
 fn main() {
     let file = fs::read_to_string("./input.txt").unwrap();
     println!("{}", process_part1(&file))
-}
\ No newline at end of file
+}
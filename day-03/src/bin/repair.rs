@@ -0,0 +1,8 @@
+use day_03::{plan_repairs, render_repairs};
+use std::fs;
+
+// Plans the fewest compartment swaps that untangle every rucksack in ./input.txt.
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!("{}", render_repairs(&plan_repairs(&file)));
+}
@@ -0,0 +1,12 @@
+use day_03::validate;
+use std::fs;
+
+// Lists every rucksack in ./input.txt that breaks the puzzle's assumptions.
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    let findings = validate(&file);
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    println!("{} findings", findings.len());
+}
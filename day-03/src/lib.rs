@@ -1,50 +1,422 @@
-#![feature(iter_next_chunk)]
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr},
+    str::FromStr,
+};
 
-use std::collections::HashSet;
+// A set of rucksack items, one bit per item at its priority: a-z are 1 to 26,
+// A-Z are 27 to 52. Bit 0 is never set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ItemSet(u64);
 
-pub fn process_part1(input: &str) -> String {
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+
+    pub fn priority(item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+
+    pub fn item(priority: u32) -> Option<char> {
+        match priority {
+            1..=26 => char::from_u32('a' as u32 + priority - 1),
+            27..=52 => char::from_u32('A' as u32 + priority - 27),
+            _ => None,
+        }
+    }
+
+    // Returns false for anything that is not an item.
+    pub fn insert(&mut self, item: char) -> bool {
+        match Self::priority(item) {
+            Some(priority) => {
+                self.0 |= 1 << priority;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(self, item: char) -> bool {
+        Self::priority(item).is_some_and(|priority| self.0 & (1 << priority) != 0)
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    // Priorities in increasing order.
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let priority = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(priority)
+        })
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        self.priorities().filter_map(Self::item)
+    }
+}
+
+impl FromStr for ItemSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = ItemSet::EMPTY;
+        for item in s.chars() {
+            if !set.insert(item) {
+                return Err(format!("Invalid item, got {:?}", item));
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        self.intersection(other)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        self.union(other)
+    }
+}
+
+// Lenient keeps the puzzle's assumptions loose: odd lengths split at the
+// floor of the middle and several common items resolve to the lowest
+// priority. Strict rejects any rucksack `check_rucksack` has a problem with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Lenient,
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    // the item in the middle belongs to neither compartment
+    OddLength { len: usize, middle: char },
+    InvalidItems(Vec<char>),
+    NoCommonItem { left: ItemSet, right: ItemSet },
+    SeveralCommonItems(ItemSet),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::OddLength { len, middle } => {
+                write!(f, "odd length {} with {} in the middle", len, middle)
+            }
+            Problem::InvalidItems(items) => write!(
+                f,
+                "invalid items {}",
+                items
+                    .iter()
+                    .map(|c| format!("{:?}", c))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            Problem::NoCommonItem { left, right } => write!(
+                f,
+                "no common item between {} and {}",
+                left.iter().collect::<String>(),
+                right.iter().collect::<String>()
+            ),
+            Problem::SeveralCommonItems(items) => {
+                write!(
+                    f,
+                    "several common items {}",
+                    items.iter().collect::<String>()
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub line: usize,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.problem)
+    }
+}
+
+pub fn check_rucksack(line: &str) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !line.len().is_multiple_of(2) {
+        problems.push(Problem::OddLength {
+            len: line.len(),
+            middle: line.chars().nth(line.chars().count() / 2).unwrap(),
+        });
+    }
+    let invalid = line
+        .chars()
+        .filter(|c| ItemSet::priority(*c).is_none())
+        .collect::<Vec<_>>();
+    if !invalid.is_empty() {
+        problems.push(Problem::InvalidItems(invalid));
+    }
+
+    // split on a char boundary so lines with multi-byte junk still get checked
+    let mid = line
+        .char_indices()
+        .nth(line.chars().count() / 2)
+        .map_or(line.len(), |(i, _)| i);
+    let (left, right) = line.split_at(mid);
+    let mut sets = [ItemSet::EMPTY; 2];
+    for (set, half) in sets.iter_mut().zip([left, right]) {
+        half.chars().for_each(|c| {
+            set.insert(c);
+        });
+    }
+    let common = sets[0] & sets[1];
+    match common.len() {
+        0 => problems.push(Problem::NoCommonItem {
+            left: sets[0],
+            right: sets[1],
+        }),
+        1 => {}
+        _ => problems.push(Problem::SeveralCommonItems(common)),
+    }
+    problems
+}
+
+pub fn validate(input: &str) -> Vec<Finding> {
     input
         .lines()
-        .map(|line| {
-            let (left, right) = line.split_at(line.len() / 2);
-            let left_set: HashSet<_> = left.chars().collect();
-            let right_set: HashSet<_> = right.chars().collect();
-            let common = *left_set.intersection(&right_set).collect::<Vec<_>>()[0];
-            if common.is_uppercase() {
-                common as u32 - 'A' as u32 + 27
-            } else {
-                common as u32 - 'a' as u32 + 1
-            }
+        .enumerate()
+        .flat_map(|(i, line)| {
+            check_rucksack(line)
+                .into_iter()
+                .map(move |problem| Finding {
+                    line: i + 1,
+                    problem,
+                })
         })
-        .sum::<u32>()
-        .to_string()
+        .collect()
 }
 
-pub fn process_part2(input: &str) -> String {
-    let mut lines = input.lines();
+fn strict_check(i: usize, line: &str) -> Result<(), String> {
+    match check_rucksack(line).into_iter().next() {
+        Some(problem) => Err(Finding {
+            line: i + 1,
+            problem,
+        }
+        .to_string()),
+        None => Ok(()),
+    }
+}
+
+pub fn compartment_sum(input: &str, mode: Mode) -> Result<u32, String> {
+    let mut sum = 0;
+    for (i, line) in input.lines().enumerate() {
+        if mode == Mode::Strict {
+            strict_check(i, line)?;
+        }
+        if !line.is_char_boundary(line.len() / 2) {
+            return Err(format!("line {}: invalid items", i + 1));
+        }
+        let (left, right) = line.split_at(line.len() / 2);
+        sum += (left.parse::<ItemSet>()? & right.parse::<ItemSet>()?)
+            .priorities()
+            .next()
+            .ok_or_else(|| format!("line {}: no common item", i + 1))?;
+    }
+    Ok(sum)
+}
+
+pub fn process_part1(input: &str) -> String {
+    compartment_sum(input, Mode::Lenient).unwrap().to_string()
+}
 
+// Sums the badge priorities of consecutive groups of `group_size` lines, the
+// badge being the one item the whole group carries.
+pub fn badge_sum(input: &str, group_size: usize, mode: Mode) -> Result<u32, String> {
+    if group_size == 0 {
+        return Err("Groups need at least one rucksack".to_string());
+    }
     let mut sum = 0;
-    while let Ok([first, second, third]) = lines.next_chunk() {
-        let first_set: HashSet<_> = first.chars().collect();
-        let second_set: HashSet<_> = second.chars().collect();
-        let third_set: HashSet<_> = third.chars().collect();
-
-        let common = *first_set
-            .intersection(&second_set)
-            .copied()
-            .collect::<HashSet<_>>()
-            .intersection(&third_set)
-            .collect::<Vec<&char>>()[0];
-
-        sum += {
-            if common.is_uppercase() {
-                common as u32 - 'A' as u32 + 27
-            } else {
-                common as u32 - 'a' as u32 + 1
+    let mut common = ItemSet::EMPTY;
+    let mut count = 0;
+    for (i, line) in input.lines().enumerate() {
+        if mode == Mode::Strict {
+            strict_check(i, line)?;
+        }
+        let items = line.parse::<ItemSet>()?;
+        common = if count == 0 { items } else { common & items };
+        count += 1;
+        if count == group_size {
+            if mode == Mode::Strict && common.len() > 1 {
+                return Err(format!(
+                    "Several common items {} in the group ending on line {}",
+                    common.iter().collect::<String>(),
+                    i + 1
+                ));
+            }
+            sum += common
+                .priorities()
+                .next()
+                .ok_or_else(|| format!("No common item in the group ending on line {}", i + 1))?;
+            count = 0;
+        }
+    }
+    if count != 0 {
+        return Err(format!(
+            "Incomplete group at the end, got {} of {} rucksacks",
+            count, group_size
+        ));
+    }
+    Ok(sum)
+}
+
+pub fn process_part2(input: &str) -> String {
+    badge_sum(input, 3, Mode::Lenient).unwrap().to_string()
+}
+
+// Swaps `(from left, from right)` that leave every item type in only one
+// compartment. Choosing which types end up on the left is a subset sum over
+// the type counts that must fill exactly half the rucksack; among those the
+// cheapest is the one that pulls the fewest items over from the right.
+pub fn plan_repair(line: &str) -> Result<Vec<(char, char)>, String> {
+    if !line.len().is_multiple_of(2) {
+        return Err(format!("odd length {}", line.len()));
+    }
+    // every valid item is one byte, so checking them first keeps the split
+    // on a char boundary
+    if let Some(item) = line.chars().find(|&c| ItemSet::priority(c).is_none()) {
+        return Err(format!("invalid item {:?}", item));
+    }
+    let half = line.len() / 2;
+    let (left, right) = line.split_at(half);
+    let mut counts = [[0usize; 2]; 53];
+    for (side, compartment) in [left, right].iter().enumerate() {
+        for item in compartment.chars() {
+            let priority =
+                ItemSet::priority(item).ok_or_else(|| format!("invalid item {:?}", item))?;
+            counts[priority as usize][side] += 1;
+        }
+    }
+    let types = (left.parse::<ItemSet>()? | right.parse::<ItemSet>()?)
+        .priorities()
+        .map(|priority| priority as usize)
+        .collect::<Vec<_>>();
+
+    // best[i][s]: fewest items moved right-to-left when the first i types put
+    // exactly s items on the left
+    let mut best = vec![vec![None; half + 1]; types.len() + 1];
+    best[0][0] = Some(0);
+    for (i, &t) in types.iter().enumerate() {
+        let [l, r] = counts[t];
+        for s in 0..=half {
+            let Some(cost) = best[i][s] else { continue };
+            let stay_right = &mut best[i + 1][s];
+            *stay_right = Some(stay_right.map_or(cost, |c: usize| c.min(cost)));
+            if s + l + r <= half {
+                let go_left = &mut best[i + 1][s + l + r];
+                *go_left = Some(go_left.map_or(cost + r, |c: usize| c.min(cost + r)));
+            }
+        }
+    }
+    if best[types.len()][half].is_none() {
+        return Err("no split of the item types fills both compartments".to_string());
+    }
+
+    let (mut to_right, mut to_left) = (vec![], vec![]);
+    let mut s = half;
+    for (i, &t) in types.iter().enumerate().rev() {
+        let [l, r] = counts[t];
+        let item = ItemSet::item(t as u32).unwrap();
+        let cost = best[i + 1][s].unwrap();
+        if best[i][s] == Some(cost) {
+            to_right.extend(std::iter::repeat_n(item, l));
+        } else {
+            s -= l + r;
+            to_left.extend(std::iter::repeat_n(item, r));
+        }
+    }
+    to_right.reverse();
+    to_left.reverse();
+    Ok(to_right.into_iter().zip(to_left).collect())
+}
+
+pub struct Repair {
+    pub line: usize,
+    pub swaps: Result<Vec<(char, char)>, String>,
+}
+
+pub fn plan_repairs(input: &str) -> Vec<Repair> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Repair {
+            line: i + 1,
+            swaps: plan_repair(line),
+        })
+        .collect()
+}
+
+// Lists the rucksacks that need work, then the totals.
+pub fn render_repairs(repairs: &[Repair]) -> String {
+    let mut lines = vec![];
+    let (mut swaps, mut repaired, mut impossible) = (0, 0, 0);
+    for repair in repairs {
+        match &repair.swaps {
+            Ok(list) if list.is_empty() => {}
+            Ok(list) => {
+                swaps += list.len();
+                repaired += 1;
+                lines.push(format!(
+                    "line {}: {} swaps: {}",
+                    repair.line,
+                    list.len(),
+                    list.iter()
+                        .map(|(left, right)| format!("{}<>{}", left, right))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                ));
+            }
+            Err(reason) => {
+                impossible += 1;
+                lines.push(format!("line {}: impossible, {}", repair.line, reason));
             }
         }
     }
-    sum.to_string()
+    lines.push(format!(
+        "{} swaps over {} rucksacks, {} impossible, {} already fine",
+        swaps,
+        repaired,
+        impossible,
+        repairs.len() - repaired - impossible
+    ));
+    lines.join("\n")
 }
 
 #[cfg(test)]
@@ -69,4 +441,92 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
         let result = process_part2(INPUT);
         assert_eq!(result, "70");
     }
+
+    #[test]
+    fn item_sets() {
+        let a: ItemSet = "abcZ".parse().unwrap();
+        let b: ItemSet = "cdZZ".parse().unwrap();
+        assert_eq!((a & b).iter().collect::<String>(), "cZ");
+        assert_eq!((a | b).len(), 5);
+        assert_eq!((a & b).priorities().collect::<Vec<_>>(), [3, 52]);
+        assert!(a.contains('Z') && !a.contains('d') && !a.contains('1'));
+        assert!((a & "xy".parse().unwrap()).is_empty());
+        assert_eq!(ItemSet::priority('p'), Some(16));
+        assert_eq!(ItemSet::item(42), Some('P'));
+        assert!("ab1".parse::<ItemSet>().is_err());
+    }
+
+    #[test]
+    fn badge_groups() {
+        assert_eq!(badge_sum(INPUT, 3, Mode::Lenient), Ok(70));
+        assert_eq!(badge_sum("abc\ncde\nxy\nyz", 2, Mode::Lenient), Ok(3 + 25));
+        assert!(badge_sum(INPUT, 6, Mode::Lenient).is_err());
+        assert!(badge_sum(INPUT, 4, Mode::Lenient).is_err());
+        assert!(badge_sum(INPUT, 0, Mode::Lenient).is_err());
+        assert!(badge_sum("ab\ncd", 2, Mode::Lenient).is_err());
+    }
+
+    #[test]
+    fn validation() {
+        assert!(validate(INPUT).is_empty());
+        assert_eq!(compartment_sum(INPUT, Mode::Strict), Ok(157));
+        assert_eq!(badge_sum(INPUT, 3, Mode::Strict), Ok(70));
+
+        let input = "abcabc\nabcd\nabcdb\nab-b";
+        let findings = validate(input);
+        assert_eq!(
+            findings.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+            [
+                "line 1: several common items abc",
+                "line 2: no common item between ab and cd",
+                "line 3: odd length 5 with c in the middle",
+                "line 4: invalid items '-'",
+            ]
+        );
+        assert_eq!(
+            findings[2].problem,
+            Problem::OddLength {
+                len: 5,
+                middle: 'c'
+            }
+        );
+        assert_eq!(compartment_sum("abcabc\nabcd", Mode::Lenient).ok(), None);
+        assert_eq!(compartment_sum("abcabc\nabcdb", Mode::Lenient), Ok(1 + 2));
+        assert_eq!(
+            compartment_sum(input, Mode::Strict),
+            Err("line 1: several common items abc".to_string())
+        );
+        assert!(badge_sum("ab\nab", 2, Mode::Strict).is_err());
+        assert_eq!(badge_sum("ab\nab", 2, Mode::Lenient), Ok(1));
+    }
+
+    #[test]
+    fn repairs() {
+        assert_eq!(plan_repair("aabbab"), Ok(vec![('b', 'a')]));
+        // the four a can never fit in a compartment of three
+        assert!(plan_repair("aaaabb").is_err());
+        assert_eq!(plan_repair("abpPpq").map(|swaps| swaps.len()), Ok(1));
+        assert_eq!(plan_repair("abc"), Err("odd length 3".to_string()));
+        assert_eq!(plan_repair("aéb"), Err("invalid item 'é'".to_string()));
+
+        for line in INPUT.lines() {
+            let swaps = plan_repair(line).unwrap();
+            let mut items = line.chars().collect::<Vec<_>>();
+            let half = items.len() / 2;
+            for (from_left, from_right) in swaps {
+                let l = items[..half].iter().position(|c| *c == from_left).unwrap();
+                let r = half + items[half..].iter().position(|c| *c == from_right).unwrap();
+                items.swap(l, r);
+            }
+            let (left, right) = items.split_at(half);
+            let left: ItemSet = left.iter().collect::<String>().parse().unwrap();
+            let right: ItemSet = right.iter().collect::<String>().parse().unwrap();
+            assert!((left & right).is_empty());
+        }
+        // sending one left-only item across makes room to bring p over
+        assert_eq!(
+            plan_repair("vJrwpWtwJgWrhcsFMMfFFhFp"),
+            Ok(vec![('v', 'p')])
+        );
+    }
 }
@@ -0,0 +1,9 @@
+use day_04::{coverage, parse_assignments};
+use std::fs;
+
+// Shows which sections the assignments in ./input.txt cover and how crowded
+// they get.
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!("{}", coverage(&parse_assignments(&file).unwrap()));
+}
@@ -0,0 +1,11 @@
+use day_04::Group;
+use std::fs;
+
+// Prints the relation matrix and group results for every line of ./input.txt.
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    for (i, line) in file.lines().enumerate() {
+        let group: Group = line.parse().unwrap();
+        println!("line {}\n{}\n", i + 1, group);
+    }
+}
@@ -0,0 +1,47 @@
+use day_04::{SectionIndex, SectionRange};
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: query <section|start-end>...";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+enum Query {
+    Section(u32),
+    Range(SectionRange),
+}
+
+// Looks up the assignments in ./input.txt that cover a section (`47`) or
+// intersect a range (`20-35`), one query per argument.
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.is_empty() {
+        usage();
+    }
+    // every query is checked before any is answered
+    let queries = args
+        .iter()
+        .map(|arg| match arg.parse::<u32>() {
+            Ok(section) => Query::Section(section),
+            Err(_) => Query::Range(arg.parse().unwrap_or_else(|message| {
+                eprintln!("{}", message);
+                usage()
+            })),
+        })
+        .collect::<Vec<_>>();
+
+    let file = fs::read_to_string("./input.txt").unwrap();
+    let index = SectionIndex::new(&file).unwrap();
+    for (arg, query) in args.iter().zip(queries) {
+        let found = match query {
+            Query::Section(section) => index.stab(section),
+            Query::Range(range) => index.intersecting(&range),
+        };
+        println!("{}: {} assignments", arg, found.len());
+        for assignment in found {
+            println!("  line {} elf {}", assignment.line, assignment.elf);
+        }
+    }
+}
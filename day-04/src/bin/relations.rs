@@ -0,0 +1,8 @@
+use day_04::RelationReport;
+use std::fs;
+
+// Counts the pairs in ./input.txt by how their two ranges relate.
+fn main() {
+    let file = fs::read_to_string("./input.txt").unwrap();
+    println!("{}", RelationReport::new(&file).unwrap());
+}
@@ -1,54 +1,509 @@
-use std::cmp;
+use std::{cmp, fmt, str::FromStr};
 
-pub fn process_part1(input: &str) -> String {
-    input
-        .lines()
-        .filter_map(|line| {
-            let (l, r) = line.split_once(",").unwrap();
-            let (ls, le) = l.split_once("-").unwrap();
-            let (rs, re) = r.split_once("-").unwrap();
-
-            let (nls, nle, nrs, nre): (u32, u32, u32, u32) = (
-                ls.parse().unwrap(),
-                le.parse().unwrap(),
-                rs.parse().unwrap(),
-                re.parse().unwrap(),
-            );
-                
-            if (nls <= nrs && nle >= nre) || (nrs <= nls && nre >= nle) {
-                return Some(1)
-            }
-            None
+// An inclusive run of section IDs such as `2-4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SectionRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl FromStr for SectionRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("Expected <start>-<end>, got {}", s))?;
+        let parse = |v: &str| {
+            v.parse::<u32>()
+                .map_err(|_| format!("Invalid section, got {}", v))
+        };
+        let range = SectionRange::new(parse(start)?, parse(end)?)
+            .ok_or_else(|| format!("Range ends before it starts, got {}", s))?;
+        Ok(range)
+    }
+}
+
+impl fmt::Display for SectionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl SectionRange {
+    pub fn new(start: u32, end: u32) -> Option<Self> {
+        (start <= end).then_some(SectionRange { start, end })
+    }
+
+    // A range always holds at least its start, so there is no `is_empty`.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.end as u64 - self.start as u64 + 1
+    }
+
+    pub fn contains(&self, other: &SectionRange) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn intersection(&self, other: &SectionRange) -> Option<SectionRange> {
+        SectionRange::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // Only ranges that overlap or sit right next to each other have a union
+    // that is itself a range.
+    pub fn union(&self, other: &SectionRange) -> Option<SectionRange> {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        (second.start <= first.end.saturating_add(1))
+            .then(|| SectionRange::new(first.start, first.end.max(second.end)).unwrap())
+    }
+
+    // Sections are whole units, so the range covers [start, end + 1) on the
+    // line and `2-3` meets `4-5`.
+    pub fn relation(&self, other: &SectionRange) -> Relation {
+        let (s1, e1) = (self.start as u64, self.end as u64 + 1);
+        let (s2, e2) = (other.start as u64, other.end as u64 + 1);
+        use Relation::*;
+        match (s1.cmp(&s2), e1.cmp(&e2)) {
+            _ if e1 < s2 => Before,
+            _ if e2 < s1 => After,
+            _ if e1 == s2 => Meets,
+            _ if e2 == s1 => MetBy,
+            (cmp::Ordering::Equal, cmp::Ordering::Equal) => Equals,
+            (cmp::Ordering::Equal, cmp::Ordering::Less) => Starts,
+            (cmp::Ordering::Equal, cmp::Ordering::Greater) => StartedBy,
+            (cmp::Ordering::Greater, cmp::Ordering::Equal) => Finishes,
+            (cmp::Ordering::Less, cmp::Ordering::Equal) => FinishedBy,
+            (cmp::Ordering::Greater, cmp::Ordering::Less) => During,
+            (cmp::Ordering::Less, cmp::Ordering::Greater) => Contains,
+            (cmp::Ordering::Less, cmp::Ordering::Less) => Overlaps,
+            (cmp::Ordering::Greater, cmp::Ordering::Greater) => OverlappedBy,
+        }
+    }
+}
+
+// Allen's interval relations, read as "first <relation> second".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl Relation {
+    pub const ALL: [Relation; 13] = [
+        Relation::Before,
+        Relation::Meets,
+        Relation::Overlaps,
+        Relation::Starts,
+        Relation::During,
+        Relation::Finishes,
+        Relation::Equals,
+        Relation::FinishedBy,
+        Relation::Contains,
+        Relation::StartedBy,
+        Relation::OverlappedBy,
+        Relation::MetBy,
+        Relation::After,
+    ];
+
+    // The usual one or two letter abbreviations, inverses marked with i.
+    pub fn symbol(self) -> &'static str {
+        [
+            "b", "m", "o", "s", "d", "f", "=", "fi", "di", "si", "oi", "mi", "bi",
+        ][self as usize]
+    }
+
+    pub fn inverse(self) -> Relation {
+        Relation::ALL[Relation::ALL.len() - 1 - self as usize]
+    }
+
+    // One of the two ranges contains the other.
+    pub fn is_containment(self) -> bool {
+        matches!(
+            self,
+            Relation::Starts
+                | Relation::During
+                | Relation::Finishes
+                | Relation::Equals
+                | Relation::FinishedBy
+                | Relation::Contains
+                | Relation::StartedBy
+        )
+    }
+
+    // The two ranges have at least one section in common.
+    pub fn shares_section(self) -> bool {
+        !matches!(
+            self,
+            Relation::Before | Relation::Meets | Relation::MetBy | Relation::After
+        )
+    }
+}
+
+pub fn parse_group(line: &str) -> Result<Vec<SectionRange>, String> {
+    line.split(',').map(str::parse).collect()
+}
+
+// The puzzle's own lines: exactly two ranges.
+pub fn parse_pair(line: &str) -> Result<(SectionRange, SectionRange), String> {
+    match parse_group(line)?.as_slice() {
+        [left, right] => Ok((*left, *right)),
+        _ => Err(format!(
+            "Expected two ranges separated by a comma, got {}",
+            line
+        )),
+    }
+}
+
+// The ranges on one line, however many elves share it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub ranges: Vec<SectionRange>,
+}
+
+impl FromStr for Group {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Group {
+            ranges: parse_group(s)?,
         })
-        .collect::<Vec<_>>()
-        .len()
-        .to_string()
+    }
 }
 
-pub fn process_part2(input: &str) -> String {
-    input
-        .lines()
-        .filter_map(|line| {
-            let (l, r) = line.split_once(",").unwrap();
-            let (ls, le) = l.split_once("-").unwrap();
-            let (rs, re) = r.split_once("-").unwrap();
-            let (nls, nle, nrs, nre): (u32, u32, u32, u32) = (
-                ls.parse().unwrap(),
-                le.parse().unwrap(),
-                rs.parse().unwrap(),
-                re.parse().unwrap(),
-            );
-
-            if cmp::max(nls, nrs) <= cmp::min(nle, nre) {
-                return Some(1)
+impl Group {
+    // `matrix[i][j]` is how range i relates to range j.
+    pub fn matrix(&self) -> Vec<Vec<Relation>> {
+        self.ranges
+            .iter()
+            .map(|a| self.ranges.iter().map(|b| a.relation(b)).collect())
+            .collect()
+    }
+
+    // The first range that contains every other one.
+    pub fn container(&self) -> Option<usize> {
+        self.ranges
+            .iter()
+            .position(|a| self.ranges.iter().all(|b| a.contains(b)))
+    }
+
+    // The sections every range in the group covers.
+    pub fn common_sections(&self) -> Option<SectionRange> {
+        let (first, rest) = self.ranges.split_first()?;
+        rest.iter()
+            .try_fold(*first, |common, range| common.intersection(range))
+    }
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels = self
+            .ranges
+            .iter()
+            .map(|range| range.to_string())
+            .collect::<Vec<_>>();
+        let width = labels
+            .iter()
+            .map(|label| label.len())
+            .max()
+            .unwrap_or(0)
+            .max(2);
+        write!(f, "{:width$}", "", width = width)?;
+        for label in &labels {
+            write!(f, " {:>width$}", label, width = width)?;
+        }
+        for (label, row) in labels.iter().zip(self.matrix()) {
+            write!(f, "\n{:>width$}", label, width = width)?;
+            for relation in row {
+                write!(f, " {:>width$}", relation.symbol(), width = width)?;
             }
-            None
-        })
-        .collect::<Vec<_>>()
-        .len()
+        }
+        match self.container() {
+            Some(i) => write!(f, "\n{} contains all others", labels[i])?,
+            None => write!(f, "\nno range contains all others")?,
+        }
+        match self.common_sections() {
+            Some(common) => write!(f, "\nall share {}", common),
+            None => write!(f, "\nno section is shared by all"),
+        }
+    }
+}
+
+// Relations are counted for every pair of ranges within a line, so a
+// two-range line adds exactly one. Containment and shared sections are
+// counted per line, over the whole group.
+#[derive(Debug, Default, PartialEq)]
+pub struct RelationReport {
+    counts: [usize; 13],
+    containing: usize,
+    sharing: usize,
+}
+
+impl RelationReport {
+    pub fn new(input: &str) -> Result<Self, String> {
+        let mut report = RelationReport::default();
+        for line in input.lines() {
+            let group: Group = line.parse()?;
+            for (i, left) in group.ranges.iter().enumerate() {
+                for right in &group.ranges[i + 1..] {
+                    report.counts[left.relation(right) as usize] += 1;
+                }
+            }
+            report.containing += group.container().is_some() as usize;
+            report.sharing += group.common_sections().is_some() as usize;
+        }
+        Ok(report)
+    }
+
+    pub fn count(&self, relation: Relation) -> usize {
+        self.counts[relation as usize]
+    }
+
+    // Lines where one range contains all the others.
+    pub fn containments(&self) -> usize {
+        self.containing
+    }
+
+    // Lines where every range shares at least one section.
+    pub fn overlaps(&self) -> usize {
+        self.sharing
+    }
+}
+
+impl fmt::Display for RelationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for relation in Relation::ALL {
+            writeln!(
+                f,
+                "{:<14} {:>6}",
+                format!("{:?}", relation),
+                self.count(relation)
+            )?;
+        }
+        writeln!(f, "{:<14} {:>6}", "containment", self.containments())?;
+        write!(f, "{:<14} {:>6}", "shared section", self.overlaps())
+    }
+}
+
+// Every range in the input, in order, regardless of which pair it belongs to.
+pub fn parse_assignments(input: &str) -> Result<Vec<SectionRange>, String> {
+    let mut ranges = vec![];
+    for line in input.lines() {
+        ranges.extend(parse_group(line)?);
+    }
+    Ok(ranges)
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Coverage {
+    // maximal runs of sections assigned to at least one elf
+    pub covered: Vec<SectionRange>,
+    // unassigned runs between the first and last covered section
+    pub gaps: Vec<SectionRange>,
+    pub max_elves: usize,
+    // runs of sections assigned to `max_elves` elves
+    pub busiest: Vec<SectionRange>,
+}
+
+// Sweeps over the range boundaries in order, so the cost is the sort of the
+// 2n boundaries no matter how long the ranges are.
+pub fn coverage(ranges: &[SectionRange]) -> Coverage {
+    let mut events = ranges
+        .iter()
+        .flat_map(|range| [(range.start as u64, 1), (range.end as u64 + 1, -1)])
+        .collect::<Vec<(u64, i64)>>();
+    events.sort_unstable();
+
+    // runs between consecutive boundaries with the number of elves on them
+    let mut runs = vec![];
+    let mut elves = 0;
+    let mut i = 0;
+    while i < events.len() {
+        let position = events[i].0;
+        while i < events.len() && events[i].0 == position {
+            elves += events[i].1;
+            i += 1;
+        }
+        if elves > 0 {
+            runs.push((position, events[i].0 - 1, elves as usize));
+        }
+    }
+
+    let mut result = Coverage {
+        max_elves: runs.iter().map(|run| run.2).max().unwrap_or(0),
+        ..Default::default()
+    };
+    let push = |list: &mut Vec<SectionRange>, start: u64, end: u64| match list.last_mut() {
+        Some(last) if last.end as u64 + 1 == start => last.end = end as u32,
+        _ => list.push(SectionRange::new(start as u32, end as u32).unwrap()),
+    };
+    for &(start, end, elves) in runs.iter() {
+        push(&mut result.covered, start, end);
+        if elves == result.max_elves {
+            push(&mut result.busiest, start, end);
+        }
+    }
+    result.gaps = result
+        .covered
+        .windows(2)
+        .map(|pair| SectionRange::new(pair[0].end + 1, pair[1].start - 1).unwrap())
+        .collect();
+    result
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |ranges: &[SectionRange]| {
+            if ranges.is_empty() {
+                return "none".to_string();
+            }
+            ranges
+                .iter()
+                .map(|range| range.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let total = |ranges: &[SectionRange]| ranges.iter().map(|r| r.len()).sum::<u64>();
+        writeln!(
+            f,
+            "covered  {} sections: {}",
+            total(&self.covered),
+            list(&self.covered)
+        )?;
+        writeln!(
+            f,
+            "gaps     {} sections: {}",
+            total(&self.gaps),
+            list(&self.gaps)
+        )?;
+        write!(
+            f,
+            "busiest  {} elves on {} sections: {}",
+            self.max_elves,
+            total(&self.busiest),
+            list(&self.busiest)
+        )
+    }
+}
+
+// Where a range came from: 1-based line number and position on that line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Assignment {
+    pub line: usize,
+    pub elf: usize,
+}
+
+// An interval tree laid out over the ranges sorted by start: the node for
+// `lo..hi` is its middle entry, and `max_end` holds the largest end within
+// each node's subtree so whole subtrees ending too early can be skipped.
+pub struct SectionIndex {
+    entries: Vec<(SectionRange, Assignment)>,
+    max_end: Vec<u32>,
+}
+
+impl SectionIndex {
+    pub fn new(input: &str) -> Result<Self, String> {
+        let mut entries = vec![];
+        for (i, line) in input.lines().enumerate() {
+            for (j, range) in parse_group(line)?.into_iter().enumerate() {
+                entries.push((
+                    range,
+                    Assignment {
+                        line: i + 1,
+                        elf: j + 1,
+                    },
+                ));
+            }
+        }
+        entries.sort_unstable();
+        let mut index = SectionIndex {
+            max_end: vec![0; entries.len()],
+            entries,
+        };
+        index.build(0, index.entries.len());
+        Ok(index)
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> u32 {
+        if lo >= hi {
+            return 0;
+        }
+        let mid = (lo + hi) / 2;
+        let max_end = self.entries[mid]
+            .0
+            .end
+            .max(self.build(lo, mid))
+            .max(self.build(mid + 1, hi));
+        self.max_end[mid] = max_end;
+        max_end
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn search(&self, lo: usize, hi: usize, query: &SectionRange, found: &mut Vec<Assignment>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if self.max_end[mid] < query.start {
+            return;
+        }
+        self.search(lo, mid, query, found);
+        let (range, assignment) = &self.entries[mid];
+        if range.start > query.end {
+            return;
+        }
+        if range.end >= query.start {
+            found.push(*assignment);
+        }
+        self.search(mid + 1, hi, query, found);
+    }
+
+    // Assignments sharing at least one section with `query`, by line and elf.
+    pub fn intersecting(&self, query: &SectionRange) -> Vec<Assignment> {
+        let mut found = vec![];
+        self.search(0, self.entries.len(), query, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    // Assignments that include `section`.
+    pub fn stab(&self, section: u32) -> Vec<Assignment> {
+        self.intersecting(&SectionRange::new(section, section).unwrap())
+    }
+}
+
+pub fn process_part1(input: &str) -> String {
+    RelationReport::new(input)
+        .unwrap()
+        .containments()
         .to_string()
 }
 
+pub fn process_part2(input: &str) -> String {
+    RelationReport::new(input).unwrap().overlaps().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +527,148 @@ mod tests {
         let result = process_part2(INPUT);
         assert_eq!(result, "4");
     }
+
+    fn range(s: &str) -> SectionRange {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn section_ranges() {
+        assert_eq!(range("2-8").len(), 7);
+        assert_eq!(range("2-6").intersection(&range("4-8")), Some(range("4-6")));
+        assert_eq!(range("2-3").intersection(&range("4-5")), None);
+        assert_eq!(range("2-3").union(&range("4-5")), Some(range("2-5")));
+        assert_eq!(range("2-3").union(&range("5-5")), None);
+        assert!("4-2".parse::<SectionRange>().is_err());
+        assert!("4".parse::<SectionRange>().is_err());
+    }
+
+    #[test]
+    fn allen_relations() {
+        use Relation::*;
+        for (pair, relation) in [
+            ("2-4,6-8", Before),
+            ("2-3,4-5", Meets),
+            ("5-7,7-9", Overlaps),
+            ("2-4,2-8", Starts),
+            ("3-7,2-8", During),
+            ("6-6,4-6", Finishes),
+            ("4-6,4-6", Equals),
+        ] {
+            let (left, right) = parse_pair(pair).unwrap();
+            assert_eq!(left.relation(&right), relation, "{}", pair);
+            assert_eq!(right.relation(&left), relation.inverse(), "{}", pair);
+        }
+
+        let report = RelationReport::new(INPUT).unwrap();
+        assert_eq!(report.count(Before), 1);
+        assert_eq!(report.count(Contains), 1);
+        assert_eq!(report.count(Finishes), 1);
+        assert_eq!(
+            Relation::ALL
+                .iter()
+                .map(|r| report.count(*r))
+                .sum::<usize>(),
+            6
+        );
+    }
+
+    #[test]
+    fn coverage_and_gaps() {
+        let ranges = parse_assignments("1-3,5-6\n10-12,2-5\n3-3,11-11").unwrap();
+        let coverage = coverage(&ranges);
+        assert_eq!(coverage.covered, [range("1-6"), range("10-12")]);
+        assert_eq!(coverage.gaps, [range("7-9")]);
+        assert_eq!(coverage.max_elves, 3);
+        assert_eq!(coverage.busiest, [range("3-3")]);
+
+        let example = super::coverage(&parse_assignments(INPUT).unwrap());
+        assert_eq!(example.covered, [range("2-9")]);
+        assert!(example.gaps.is_empty());
+        assert_eq!(example.max_elves, 8);
+        assert_eq!(example.busiest, [range("6-6")]);
+        assert_eq!(super::coverage(&[]), Coverage::default());
+
+        // every possible section, one more than fits in a u32
+        let full = super::coverage(&parse_assignments("0-4294967295,0-4294967295").unwrap());
+        assert_eq!(full.covered[0].len(), 1 << 32);
+        assert!(full.to_string().starts_with("covered  4294967296 sections"));
+    }
+
+    #[test]
+    fn groups() {
+        let group: Group = "2-8,3-7,4-4,2-5".parse().unwrap();
+        assert_eq!(group.container(), Some(0));
+        assert_eq!(group.common_sections(), Some(range("4-4")));
+        assert_eq!(group.matrix()[1][3], Relation::OverlappedBy);
+        assert_eq!(
+            group.to_string().lines().nth(1),
+            Some("2-8   =  di  di  si")
+        );
+
+        let group: Group = "1-3,2-4,3-5".parse().unwrap();
+        assert_eq!(group.container(), None);
+        assert_eq!(group.common_sections(), Some(range("3-3")));
+        assert_eq!(group.matrix()[0][2], Relation::Overlaps);
+        assert!(parse_pair("1-3,2-4,3-5").is_err());
+
+        // with two ranges the group results are the two puzzle questions
+        for line in INPUT.lines() {
+            let (left, right) = parse_pair(line).unwrap();
+            let group: Group = line.parse().unwrap();
+            let relation = left.relation(&right);
+            assert_eq!(group.container().is_some(), relation.is_containment());
+            assert_eq!(group.common_sections().is_some(), relation.shares_section());
+        }
+    }
+
+    #[test]
+    fn section_index() {
+        let index = SectionIndex::new(INPUT).unwrap();
+        assert_eq!(index.len(), 12);
+        let at = |line, elf| Assignment { line, elf };
+        assert_eq!(index.stab(1), []);
+        assert_eq!(index.stab(9), [at(3, 2)]);
+        assert_eq!(
+            index.stab(3),
+            [at(1, 1), at(2, 1), at(4, 1), at(4, 2), at(6, 1)]
+        );
+        assert_eq!(
+            index.intersecting(&range("8-20")),
+            [at(1, 2), at(3, 2), at(4, 1), at(6, 2)]
+        );
+
+        // agrees with checking every range
+        let ranges = INPUT
+            .lines()
+            .map(|line| parse_group(line).unwrap())
+            .collect::<Vec<_>>();
+        for query in ["1-2", "4-4", "5-7", "2-9", "10-11"] {
+            let query = range(query);
+            let expected = ranges
+                .iter()
+                .enumerate()
+                .flat_map(|(i, group)| {
+                    group
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, r)| r.intersection(&query).is_some())
+                        .map(move |(j, _)| at(i + 1, j + 1))
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(index.intersecting(&query), expected);
+        }
+    }
+
+    #[test]
+    fn parts_on_groups() {
+        assert_eq!(process_part1("1-3,2-4,3-5"), "0");
+        assert_eq!(process_part2("1-3,2-4,3-5"), "1");
+        // 1-4 contains both halves that share nothing
+        assert_eq!(process_part1("1-9,2-4,3-5\n1-2,3-4,1-4"), "2");
+        assert_eq!(process_part2("1-9,2-4,3-5\n1-2,3-4,1-4"), "1");
+
+        let report = RelationReport::new("1-3,2-4,3-5").unwrap();
+        assert_eq!(report.count(Relation::Overlaps), 3);
+    }
 }
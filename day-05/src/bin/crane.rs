@@ -0,0 +1,16 @@
+use day_05::{crane, rearrange};
+use std::{env, fs};
+
+// Runs the procedure in ./input.txt with the crane named by the argument:
+// 9000, 9001, chunked:<k> or sorted.
+fn main() {
+    let name = env::args()
+        .nth(1)
+        .expect("usage: crane <9000|9001|chunked:k|sorted>");
+    let crane = crane(&name).unwrap();
+    let file = fs::read_to_string("./input.txt").unwrap();
+    match rearrange(&file, crane.as_ref()) {
+        Ok(tops) => println!("{}: {}", crane.name(), tops),
+        Err(message) => println!("{}: {}", crane.name(), message),
+    }
+}
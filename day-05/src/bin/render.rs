@@ -0,0 +1,61 @@
+use day_05::{crane, parse_input, steps};
+use std::{env, fs, process};
+
+const USAGE: &str = "usage: render [--crane <name>] [--all | --stack <s> | --step <n>]";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+// Draws the stacks of ./input.txt as the procedure runs.
+//   render [--crane <name>]              final state
+//   render --step <n>                    state after step n
+//   render --all                         every state
+//   render --stack <s>                   every state where stack s changed
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let value = |flag: &str| {
+        args.iter().position(|arg| arg == flag).map(|i| {
+            args.get(i + 1)
+                .map(String::as_str)
+                .unwrap_or_else(|| usage())
+        })
+    };
+    let number = |flag: &str| value(flag).map(|v| v.parse::<usize>().unwrap_or_else(|_| usage()));
+    let crane = crane(value("--crane").unwrap_or("9000")).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        usage()
+    });
+
+    let file = fs::read_to_string("./input.txt").unwrap();
+    let (stacks, actions) = parse_input(&file).unwrap();
+    let count = stacks.crates().len();
+    let steps = steps(stacks, &actions, crane.as_ref()).unwrap();
+
+    let shown = if args.iter().any(|arg| arg == "--all") {
+        steps.iter().collect::<Vec<_>>()
+    } else if let Some(stack) = number("--stack") {
+        if !(1..=count).contains(&stack) {
+            eprintln!("Stacks are numbered 1 to {}, got {}", count, stack);
+            usage();
+        }
+        steps
+            .iter()
+            .filter(|step| step.touches(stack - 1))
+            .collect()
+    } else if let Some(step) = number("--step") {
+        match steps.get(step) {
+            Some(step) => vec![step],
+            None => {
+                eprintln!("Steps run from 0 to {}, got {}", steps.len() - 1, step);
+                usage();
+            }
+        }
+    } else {
+        vec![steps.last().unwrap()]
+    };
+    for step in shown {
+        println!("{}\n", step.render());
+    }
+}
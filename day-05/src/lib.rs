@@ -1,42 +1,16 @@
+use std::fmt;
+
 use nom::{
-    character::complete::{alpha1, newline},
-    character::complete,
-    bytes::complete::{tag, take_until},
-    multi::separated_list1,
-    sequence::delimited,
-    branch::alt,
-    IResult,
+    bytes::complete::tag, character::complete, character::complete::newline,
+    multi::separated_list1, IResult,
 };
 
-
 #[derive(Debug)]
 pub struct Action {
-    count: u32,
-    from: u32,
-    to: u32,
-}
-
-pub fn parse_crate(input: &str) -> IResult<&str, Option<&str>> {
-    let (input, c) = alt((tag("   "), delimited(tag("["), alpha1, tag("]"))))(input)?;
-    let result = match c {
-        "   " => None,
-        val => Some(val),
-    };
-    Ok((input, result))
-}
-
-pub fn parse_crate_line(input: &str) -> IResult<&str, Vec<Option<&str>>> {
-    separated_list1(tag(" "), parse_crate)(input)
-}
-
-pub fn parse_crate_lines(input: &str) -> IResult<&str, Vec<Vec<Option<&str>>>> {
-    let (input, crates) = separated_list1(newline, parse_crate_line)(input)?;
-    let (input, _) = take_until("\n")(input)?;
-    let (input, _) = newline(input)?;
-    let (input, _) = take_until("\n")(input)?;
-    let (input, _) = newline(input)?;
-    let (input, _) = newline(input)?;
-    Ok((input, crates))
+    pub count: u32,
+    // zero-based stack indices
+    pub from: u32,
+    pub to: u32,
 }
 
 pub fn parse_action_line(input: &str) -> IResult<&str, Action> {
@@ -50,7 +24,7 @@ pub fn parse_action_line(input: &str) -> IResult<&str, Action> {
     Ok((
         input,
         Action {
-            count: count,
+            count,
             from: from - 1,
             to: to - 1,
         },
@@ -61,66 +35,414 @@ pub fn parse_action_lines(input: &str) -> IResult<&str, Vec<Action>> {
     separated_list1(newline, parse_action_line)(input)
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (input, crate_lines) = parse_crate_lines(input).unwrap();
-    let mut stacks: Vec<Vec<&str>> = vec![vec![]; crate_lines[0].len()];
-    for line in crate_lines.iter().rev() {
-        for (i, val) in line.iter().enumerate() {
-            if let Some(v) = val {
-                stacks[i].push(v);
+// The starting drawing. Every column is `width` characters wide, with a
+// single space between columns; a crate is its label in brackets, centred
+// like the stack number in the footer below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stacks<'a> {
+    // bottom to top; private so no label can outgrow `width`
+    crates: Vec<Vec<&'a str>>,
+    width: usize,
+}
+
+// Pads `text` to `width`, centred and leaning left when it cannot be exact.
+fn centre(text: &str, width: usize) -> String {
+    let left = (width - text.len()) / 2;
+    format!(
+        "{}{}{}",
+        " ".repeat(left),
+        text,
+        " ".repeat(width - text.len() - left)
+    )
+}
+
+impl<'a> Stacks<'a> {
+    // Uses the narrowest columns that fit every label and stack number.
+    pub fn new(crates: Vec<Vec<&'a str>>) -> Self {
+        let width = crates
+            .iter()
+            .flatten()
+            .map(|label| label.len() + 2)
+            .chain([3, crates.len().to_string().len()])
+            .max()
+            .unwrap();
+        Stacks { crates, width }
+    }
+
+    // Lines may be missing their trailing spaces, but every cell that is
+    // there has to be blank or a bracketed label in its column.
+    pub fn parse(drawing: &'a str) -> Result<Self, String> {
+        let lines = drawing.lines().collect::<Vec<_>>();
+        let (footer, rows) = lines
+            .split_last()
+            .ok_or("Empty drawing, expected at least the numbered footer")?;
+        if footer.contains('\t') {
+            return Err(format!("Tabs are not supported, got {:?}", footer));
+        }
+        let numbers = footer.split_whitespace().collect::<Vec<_>>();
+        if numbers.is_empty()
+            || numbers
+                .iter()
+                .enumerate()
+                .any(|(i, number)| *number != (i + 1).to_string())
+        {
+            return Err(format!("Expected stacks numbered from 1, got {:?}", footer));
+        }
+        let count = numbers.len();
+        // editors may have trimmed the trailing padding, so with a single
+        // stack the widest row is as good a guide as the footer
+        let width = match (footer.find('1'), footer.find(" 2")) {
+            _ if count == 1 => rows
+                .iter()
+                .chain([footer])
+                .map(|line| line.len())
+                .max()
+                .unwrap(),
+            (Some(first), Some(second)) if second > first => second - first,
+            _ => return Err(format!("Footer does not line up, got {:?}", footer)),
+        };
+        if width < count.to_string().len() {
+            return Err(format!("Footer columns are too narrow, got {:?}", footer));
+        }
+        let mut stacks = Stacks {
+            crates: vec![vec![]; count],
+            width,
+        };
+        if stacks.footer().trim_end() != footer.trim_end() {
+            return Err(format!("Footer does not line up, got {:?}", footer));
+        }
+
+        for (r, row) in rows.iter().enumerate().rev() {
+            let full = count * (width + 1) - 1;
+            if row.contains('\t') {
+                return Err(format!("Tabs are not supported, got {:?}", row));
+            }
+            if row.len() > full {
+                return Err(format!(
+                    "Expected rows of at most {} characters, got {:?}",
+                    full, row
+                ));
+            }
+            for i in 0..count {
+                // cells missing at the end of a trimmed row are empty
+                let start = i * (width + 1);
+                if start >= row.len() {
+                    break;
+                }
+                let cell = row
+                    .get(start..row.len().min(start + width))
+                    .ok_or_else(|| format!("Invalid cell in {:?}", row))?;
+                if i > 0 && row.as_bytes()[start - 1] != b' ' {
+                    return Err(format!("Expected a space between cells, got {:?}", row));
+                }
+                if cell.trim().is_empty() {
+                    continue;
+                }
+                let label = cell
+                    .trim()
+                    .strip_prefix('[')
+                    .and_then(|label| label.strip_suffix(']'))
+                    .filter(|label| {
+                        !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric())
+                    })
+                    .ok_or_else(|| format!("Invalid crate {:?}", cell))?;
+                if centre(&format!("[{}]", label), width).trim_end() != cell.trim_end() {
+                    return Err(format!(
+                        "Crate is not centred in its column, got {:?}",
+                        cell
+                    ));
+                }
+                if stacks.crates[i].len() + 1 != rows.len() - r {
+                    return Err(format!("Crate {} floats above an empty cell", label));
+                }
+                stacks.crates[i].push(label);
             }
         }
+        Ok(stacks)
     }
 
-    let (_, actions) = parse_action_lines(input).unwrap();
+    pub fn crates(&self) -> &[Vec<&'a str>] {
+        &self.crates
+    }
 
-    for action in actions.iter() {
-        for _ in 0..action.count {
-            let to_move = stacks[action.from as usize].pop().unwrap();
-            stacks[action.to as usize].push(to_move);
+    fn footer(&self) -> String {
+        (1..=self.crates.len())
+            .map(|i| centre(&i.to_string(), self.width))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // The drawing with `highlight(stack, height)` crates in `(A)` instead of
+    // `[A]`.
+    pub fn render(&self, highlight: impl Fn(usize, usize) -> bool) -> String {
+        let height = self.crates.iter().map(Vec::len).max().unwrap_or(0);
+        let mut lines = vec![];
+        for row in (0..height).rev() {
+            lines.push(
+                self.crates
+                    .iter()
+                    .enumerate()
+                    .map(|(i, stack)| match stack.get(row) {
+                        Some(label) if highlight(i, row) => {
+                            centre(&format!("({})", label), self.width)
+                        }
+                        Some(label) => centre(&format!("[{}]", label), self.width),
+                        None => " ".repeat(self.width),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
         }
+        lines.push(self.footer());
+        lines.join("\n")
     }
 
+    pub fn tops(&self) -> String {
+        self.crates
+            .iter()
+            .filter_map(|stack| stack.last())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("")
+    }
+}
+
+impl fmt::Display for Stacks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|_, _| false))
+    }
+}
+
+fn take<'a>(stacks: &mut [Vec<&'a str>], from: u32, count: usize) -> Result<Vec<&'a str>, String> {
+    let stack = stacks
+        .get_mut(from as usize)
+        .ok_or_else(|| format!("No stack {}", from + 1))?;
+    if stack.len() < count {
+        return Err(format!(
+            "Stack {} holds {} crates, cannot take {}",
+            from + 1,
+            stack.len(),
+            count
+        ));
+    }
+    Ok(stack.split_off(stack.len() - count))
+}
+
+fn put<'a>(stacks: &mut [Vec<&'a str>], to: u32, crates: Vec<&'a str>) -> Result<(), String> {
     stacks
-        .iter()
-        .filter_map(|stack| stack.last())
-        .collect::<Vec<_>>()
-        .into_iter()
-        .map(|val| *val)
-        .collect::<Vec<_>>()
-        .join("")
+        .get_mut(to as usize)
+        .ok_or_else(|| format!("No stack {}", to + 1))?
+        .extend(crates);
+    Ok(())
 }
 
-pub fn process_part2(input: &str) -> String {
-    let (input, crate_lines) = parse_crate_lines(input).unwrap();
-    let mut stacks: Vec<Vec<&str>> = vec![vec![]; crate_lines[0].len()];
-    for line in crate_lines.iter().rev() {
-        for (i, val) in line.iter().enumerate() {
-            if let Some(v) = val {
-                stacks[i].push(v);
-            }
+// How one action moves crates between the stacks.
+pub trait Crane {
+    fn name(&self) -> String;
+    fn apply(&self, stacks: &mut [Vec<&str>], action: &Action) -> Result<(), String>;
+}
+
+// One crate at a time, so the moved crates end up reversed.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn apply(&self, stacks: &mut [Vec<&str>], action: &Action) -> Result<(), String> {
+        for _ in 0..action.count {
+            let crates = take(stacks, action.from, 1)?;
+            put(stacks, action.to, crates)?;
+        }
+        Ok(())
+    }
+}
+
+// All crates at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn apply(&self, stacks: &mut [Vec<&str>], action: &Action) -> Result<(), String> {
+        let crates = take(stacks, action.from, action.count as usize)?;
+        put(stacks, action.to, crates)
+    }
+}
+
+// Up to `k` crates at a time, each chunk keeping its order.
+pub struct Chunked(pub usize);
+
+impl Crane for Chunked {
+    fn name(&self) -> String {
+        format!("chunked crane of capacity {}", self.0)
+    }
+
+    fn apply(&self, stacks: &mut [Vec<&str>], action: &Action) -> Result<(), String> {
+        let mut left = action.count as usize;
+        while left > 0 {
+            let chunk = left.min(self.0);
+            let crates = take(stacks, action.from, chunk)?;
+            put(stacks, action.to, crates)?;
+            left -= chunk;
         }
+        Ok(())
     }
+}
 
-    let (_, actions) = parse_action_lines(input).unwrap();
+// Moves one crate at a time like the 9000, but only onto an empty stack or
+// one whose top label sorts lower than the crate, and fails otherwise.
+pub struct Sorted;
+
+impl Crane for Sorted {
+    fn name(&self) -> String {
+        "sorting crane".to_string()
+    }
 
-    for action in actions.iter() {
-        let mut tmp = vec![];
+    fn apply(&self, stacks: &mut [Vec<&str>], action: &Action) -> Result<(), String> {
         for _ in 0..action.count {
-            tmp.push(stacks[action.from as usize].pop().unwrap());
+            let crates = take(stacks, action.from, 1)?;
+            if let Some(top) = stacks
+                .get(action.to as usize)
+                .and_then(|stack| stack.last())
+            {
+                if *top >= crates[0] {
+                    return Err(format!(
+                        "Cannot put {} onto {} on stack {}",
+                        crates[0],
+                        top,
+                        action.to + 1
+                    ));
+                }
+            }
+            put(stacks, action.to, crates)?;
         }
-        tmp.reverse();
-        stacks[action.to as usize].extend(tmp);
+        Ok(())
     }
+}
 
-    stacks
-        .iter()
-        .filter_map(|stack| stack.last())
-        .collect::<Vec<_>>()
-        .into_iter()
-        .map(|val| *val)
-        .collect::<Vec<_>>()
-        .join("")
+// `9000`, `9001`, `chunked:<k>` or `sorted`.
+pub fn crane(name: &str) -> Result<Box<dyn Crane>, String> {
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        "sorted" => Ok(Box::new(Sorted)),
+        _ => match name.strip_prefix("chunked:").map(str::parse::<usize>) {
+            Some(Ok(k)) if k > 0 => Ok(Box::new(Chunked(k))),
+            _ => Err(format!("Unknown crane, got {}", name)),
+        },
+    }
+}
+
+// The drawing and the actions are separated by a blank line.
+pub fn parse_input(input: &str) -> Result<(Stacks<'_>, Vec<Action>), String> {
+    let (drawing, actions) = input
+        .split_once("\n\n")
+        .ok_or("Expected a blank line between the drawing and the actions")?;
+    let (_, actions) =
+        parse_action_lines(actions).map_err(|e| format!("Invalid actions: {}", e))?;
+    Ok((Stacks::parse(drawing)?, actions))
+}
+
+pub fn rearrange(input: &str, crane: &dyn Crane) -> Result<String, String> {
+    let (mut stacks, actions) = parse_input(input)?;
+
+    for (i, action) in actions.iter().enumerate() {
+        crane
+            .apply(&mut stacks.crates, action)
+            .map_err(|message| format!("Step {}: {}", i + 1, message))?;
+    }
+
+    Ok(stacks.tops())
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
+// The stacks after `action`, the `number`-th step of the procedure (0 being
+// the starting drawing with no action).
+pub struct Step<'a> {
+    pub number: usize,
+    pub action: Option<&'a Action>,
+    pub stacks: Stacks<'a>,
+}
+
+impl Step<'_> {
+    // Every crane leaves the crates it moved on top of the target stack.
+    pub fn moved(&self, stack: usize, height: usize) -> bool {
+        self.action.is_some_and(|action| {
+            action.to as usize == stack
+                && height + action.count as usize >= self.stacks.crates[stack].len()
+        })
+    }
+
+    pub fn touches(&self, stack: usize) -> bool {
+        self.action
+            .is_some_and(|action| action.from as usize == stack || action.to as usize == stack)
+    }
+
+    // The applied action over the drawing, with the crates that just moved in
+    // `(A)` rather than `[A]`.
+    pub fn render(&self) -> String {
+        let title = match self.action {
+            Some(action) => format!("after step {}: {}", self.number, action),
+            None => "start".to_string(),
+        };
+        let drawing = self
+            .stacks
+            .render(|stack, height| self.moved(stack, height))
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("{}\n{}", title, drawing)
+    }
+}
+
+// Plays the procedure and returns the starting state followed by the state
+// after every action.
+pub fn steps<'a>(
+    stacks: Stacks<'a>,
+    actions: &'a [Action],
+    crane: &dyn Crane,
+) -> Result<Vec<Step<'a>>, String> {
+    let mut steps = vec![Step {
+        number: 0,
+        action: None,
+        stacks,
+    }];
+    for (i, action) in actions.iter().enumerate() {
+        let mut stacks = steps.last().unwrap().stacks.clone();
+        crane
+            .apply(&mut stacks.crates, action)
+            .map_err(|message| format!("Step {}: {}", i + 1, message))?;
+        steps.push(Step {
+            number: i + 1,
+            action: Some(action),
+            stacks,
+        });
+    }
+    Ok(steps)
+}
+
+pub fn process_part1(input: &str) -> String {
+    rearrange(input, &CrateMover9000).unwrap()
+}
+
+pub fn process_part2(input: &str) -> String {
+    rearrange(input, &CrateMover9001).unwrap()
 }
 
 #[cfg(test)]
@@ -148,4 +470,103 @@ move 1 from 1 to 2";
         let result = process_part2(INPUT);
         assert_eq!(result, "MCD");
     }
+
+    #[test]
+    fn cranes() {
+        assert_eq!(
+            rearrange(INPUT, crane("9000").unwrap().as_ref()),
+            Ok("CMZ".to_string())
+        );
+        assert_eq!(
+            rearrange(INPUT, crane("9001").unwrap().as_ref()),
+            Ok("MCD".to_string())
+        );
+        // capacity 1 is the 9000, and a capacity above every count the 9001
+        assert_eq!(rearrange(INPUT, &Chunked(1)), Ok("CMZ".to_string()));
+        assert_eq!(rearrange(INPUT, &Chunked(3)), Ok("MCD".to_string()));
+        // the 3 crates of step 2 go over as N D and then Z
+        assert_eq!(rearrange(INPUT, &Chunked(2)), Ok("MCZ".to_string()));
+        assert_eq!(
+            rearrange(INPUT, &Sorted),
+            Err("Step 1: Cannot put D onto N on stack 1".to_string())
+        );
+        assert!(crane("chunked:0").is_err());
+        assert!(crane("9002").is_err());
+    }
+
+    #[test]
+    fn rendering() {
+        let (stacks, actions) = parse_input(INPUT).unwrap();
+        let steps = steps(stacks, &actions, &CrateMover9000).unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(
+            steps[0].render(),
+            "start\n    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3"
+        );
+        snapshot::assert_snapshot!("step-2", steps[2].render());
+        assert!(steps[2].touches(0) && !steps[2].touches(1));
+        assert_eq!(steps.iter().filter(|step| step.touches(1)).count(), 3);
+    }
+
+    #[test]
+    fn drawings_round_trip() {
+        let drawing = INPUT.split_once("\n\n").unwrap().0;
+        let stacks = Stacks::parse(drawing).unwrap();
+        assert_eq!(
+            stacks.crates,
+            [vec!["Z", "N"], vec!["M", "C", "D"], vec!["P"]]
+        );
+        assert_eq!(stacks.to_string(), drawing);
+        assert_eq!(Stacks::new(stacks.crates.clone()), stacks);
+
+        let mut crates = vec![vec![]; 11];
+        crates[0] = vec!["A", "BC"];
+        crates[10] = vec!["XYZ"];
+        crates[4] = vec!["Q"];
+        let wide = Stacks::new(crates);
+        let drawing = wide.to_string();
+        assert_eq!(
+            drawing.lines().last(),
+            Some("  1     2     3     4     5     6     7     8     9    10    11  ")
+        );
+        assert!(drawing.starts_with("[BC] "));
+        assert_eq!(Stacks::parse(&drawing), Ok(wide));
+
+        let file = std::fs::read_to_string("input.txt").unwrap_or_default();
+        if let Some((drawing, _)) = file.split_once("\n\n") {
+            assert_eq!(Stacks::parse(drawing).unwrap().to_string(), drawing);
+        }
+
+        for malformed in [
+            "[A] [B] [C]\n 1   2",
+            "[A]\t[B]\n 1   2",
+            "[A] [B]\n 1\t2",
+            "[A] [B\n 1   2",
+            "[A] {B}\n 1   2 ",
+            "[A]    \n    [B]\n 1   2 ",
+            "[A]  [B]\n 1   2 ",
+            "[A] [B]\n 1   3 ",
+            "",
+        ] {
+            assert!(Stacks::parse(malformed).is_err(), "{:?}", malformed);
+        }
+    }
+
+    #[test]
+    fn trimmed_drawings() {
+        let (drawing, actions) = INPUT.split_once("\n\n").unwrap();
+        let trimmed = drawing
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(trimmed.lines().next(), Some("    [D]"));
+        assert_eq!(Stacks::parse(&trimmed), Stacks::parse(drawing));
+        assert_eq!(process_part1(&format!("{}\n\n{}", trimmed, actions)), "CMZ");
+
+        // the stack count comes from the footer even if no row reaches it
+        let stacks = Stacks::parse("[A]\n[B] [C]\n 1   2   3   4").unwrap();
+        assert_eq!(stacks.crates, [vec!["B", "A"], vec!["C"], vec![], vec![]]);
+        assert_eq!(Stacks::parse("[AB]\n 1").unwrap().crates, [vec!["AB"]]);
+    }
 }
@@ -20,6 +20,32 @@ impl<'a> FileSystem<'a> {
         }
     }
 
+    fn from_operations(operations: &'a [Operation<'a>]) -> Self {
+        let mut fs = FileSystem::new();
+        for op in operations.iter() {
+            match op {
+                Operation::Cd(cd_op) => fs.change_dir(cd_op),
+                Operation::Ls(ls_op) => fs.add_files(ls_op),
+            }
+        }
+        fs
+    }
+
+    fn path(&self, dir: usize) -> String {
+        let mut names = vec![];
+        let mut current = Some(dir);
+        while let Some(idx) = current {
+            names.push(self.dirs[idx].name);
+            current = self.dirs[idx].parent;
+        }
+        // the root itself has no name, so it contributes the leading slash
+        names.reverse();
+        match names.join("/") {
+            path if path.is_empty() => "/".to_string(),
+            path => path,
+        }
+    }
+
     fn add_file(&mut self, ls_op: &'a LsOp) {
         match ls_op {
             LsOp::File(size) => {
@@ -74,18 +100,18 @@ struct Dir<'a> {
     dirs: Vec<usize>,
 }
 
-enum Operation<'a> {
+pub enum Operation<'a> {
     Cd(CdOp<'a>),
     Ls(Vec<LsOp<'a>>),
 }
 
-enum CdOp<'a> {
+pub enum CdOp<'a> {
     Root,
     Out,
     In(&'a str),
 }
 
-enum LsOp<'a> {
+pub enum LsOp<'a> {
     File(usize),
     Dir(&'a str),
 }
@@ -122,21 +148,31 @@ fn parse_cd(input: &str) -> IResult<&str, Operation> {
     };
     Ok((input, op))
 }
-fn parse_commands(input: &str) -> IResult<&str, Vec<Operation>> {
+pub fn parse_commands(input: &str) -> IResult<&str, Vec<Operation>> {
     Ok(separated_list1(newline, alt((parse_ls, parse_cd)))(input)?)
 }
 
-pub fn process_part1(input: &str) -> String {
+pub struct DirSize {
+    pub path: String,
+    pub size: usize,
+}
+
+// Every directory with its absolute path and total size, root first.
+pub fn directory_sizes(input: &str) -> Vec<DirSize> {
     let (_, operations) = parse_commands(input).unwrap();
+    let fs = FileSystem::from_operations(&operations);
 
-    let mut fs = FileSystem::new();
+    (0..fs.dirs.len())
+        .map(|dir| DirSize {
+            path: fs.path(dir),
+            size: fs.dirs[dir].size,
+        })
+        .collect()
+}
 
-    for op in operations.iter() {
-        match op {
-            Operation::Cd(cd_op) => fs.change_dir(cd_op),
-            Operation::Ls(ls_op) => fs.add_files(ls_op),
-        }
-    }
+pub fn process_part1(input: &str) -> String {
+    let (_, operations) = parse_commands(input).unwrap();
+    let fs = FileSystem::from_operations(&operations);
 
     fs.dirs
         .iter()
@@ -153,15 +189,7 @@ pub fn process_part1(input: &str) -> String {
 
 pub fn process_part2(input: &str) -> String {
     let (_, operations) = parse_commands(input).unwrap();
-
-    let mut fs = FileSystem::new();
-
-    for op in operations.iter() {
-        match op {
-            Operation::Cd(cd_op) => fs.change_dir(cd_op),
-            Operation::Ls(ls_op) => fs.add_files(ls_op),
-        }
-    }
+    let fs = FileSystem::from_operations(&operations);
 
     let total_space = 70000000;
     let need_unused = 30000000;
@@ -226,4 +254,21 @@ $ ls
         let result = process_part2(INPUT);
         assert_eq!(result, "24933642");
     }
+
+    #[test]
+    fn directories() {
+        let dirs = directory_sizes(INPUT)
+            .into_iter()
+            .map(|dir| (dir.path, dir.size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dirs,
+            [
+                ("/".to_string(), 48381165),
+                ("/a".to_string(), 94853),
+                ("/d".to_string(), 24933642),
+                ("/a/e".to_string(), 584),
+            ]
+        );
+    }
 }
@@ -10,7 +10,7 @@ use nom::{
 };
 
 #[derive(Debug, Clone, Copy)]
-enum Move {
+pub enum Move {
     Up,
     Down,
     Right,
@@ -27,7 +27,7 @@ fn parse_move(input: &str) -> IResult<&str, Move> {
     Ok((input, dir))
 }
 
-fn parse_moves(input: &str) -> IResult<&str, Vec<Move>> {
+pub fn parse_moves(input: &str) -> IResult<&str, Vec<Move>> {
     let (input, parsed_lines) = separated_list1(
         complete::newline,
         separated_pair(parse_move, tag(" "), complete::u32),
@@ -4,12 +4,12 @@ use nom::{
 };
 
 #[derive(Debug, Clone, Copy)]
-enum Operation {
+pub enum Operation {
     Noop,
     Add(i32),
 }
 
-fn parse_operations(input: &str) -> IResult<&str, Vec<Operation>> {
+pub fn parse_operations(input: &str) -> IResult<&str, Vec<Operation>> {
     let (input, mut operations) = separated_list1(
         complete::newline,
         alt((
@@ -235,14 +235,6 @@ noop";
     #[test]
     fn part2() {
         let result = process_part2(INPUT);
-        assert_eq!(
-            result,
-            "##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######....."
-        );
+        snapshot::assert_snapshot!("part2", result);
     }
 }
@@ -22,7 +22,7 @@ struct MonkeyTest {
 }
 
 #[derive(Debug)]
-struct Monkey {
+pub struct Monkey {
     items: Vec<u64>,
     operation: Operation,
     test: MonkeyTest,
@@ -78,7 +78,7 @@ fn parse_monkey(input: &str) -> IResult<&str, Monkey> {
     ))
 }
 
-fn parse_monkeys(input: &str) -> IResult<&str, Vec<Monkey>> {
+pub fn parse_monkeys(input: &str) -> IResult<&str, Vec<Monkey>> {
     separated_list1(tag("\n\n"), parse_monkey)(input)
 }
 
@@ -107,50 +107,80 @@ impl Monkey {
     }
 }
 
-pub fn process_part1(input: &str) -> String {
-    let (_, mut monkeys) = parse_monkeys(input).unwrap();
+pub struct Simulation {
+    monkeys: Vec<Monkey>,
+    with_relief: bool,
+    least_common_multiple: u64,
+    item_counts: Vec<u64>,
+    rounds: usize,
+}
 
-    // since we have all prime numbers in input, this would work
-    let least_common_multiple = monkeys
-        .iter()
-        .map(|monkey| monkey.test.divisible_by)
-        .product::<u64>();
+impl Simulation {
+    pub fn new(input: &str, with_relief: bool) -> Self {
+        let (_, monkeys) = parse_monkeys(input).unwrap();
+
+        // since we have all prime numbers in input, this would work
+        let least_common_multiple = monkeys
+            .iter()
+            .map(|monkey| monkey.test.divisible_by)
+            .product::<u64>();
+
+        Simulation {
+            item_counts: vec![0; monkeys.len()],
+            monkeys,
+            with_relief,
+            least_common_multiple,
+            rounds: 0,
+        }
+    }
 
-    let mut item_counts: Vec<u64> = vec![0; monkeys.len()];
-    for _ in 0..20 {
-        for i in 0..monkeys.len() {
-            item_counts[i] += monkeys[i].items.len() as u64;
-            for (item, to_monkey) in monkeys[i].throw_items(true, least_common_multiple) {
-                monkeys[to_monkey].items.push(item);
+    pub fn round(&mut self) {
+        for i in 0..self.monkeys.len() {
+            self.item_counts[i] += self.monkeys[i].items.len() as u64;
+            let thrown = self.monkeys[i].throw_items(self.with_relief, self.least_common_multiple);
+            for (item, to_monkey) in thrown {
+                self.monkeys[to_monkey].items.push(item);
             }
         }
+        self.rounds += 1;
     }
 
-    item_counts.sort_by(|a, b| b.cmp(a));
-    item_counts.iter().take(2).product::<u64>().to_string()
-}
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
 
-pub fn process_part2(input: &str) -> String {
-    let (_, mut monkeys) = parse_monkeys(input).unwrap();
+    pub fn items(&self) -> Vec<&[u64]> {
+        self.monkeys
+            .iter()
+            .map(|monkey| monkey.items.as_slice())
+            .collect()
+    }
 
-    // since we have all prime numbers in input, this would work
-    let least_common_multiple = monkeys
-        .iter()
-        .map(|monkey| monkey.test.divisible_by)
-        .product::<u64>();
+    pub fn inspections(&self) -> &[u64] {
+        &self.item_counts
+    }
 
-    let mut item_counts: Vec<u64> = vec![0; monkeys.len()];
-    for _ in 0..10000 {
-        for i in 0..monkeys.len() {
-            item_counts[i] += monkeys[i].items.len() as u64;
-            for (item, to_monkey) in monkeys[i].throw_items(false, least_common_multiple) {
-                monkeys[to_monkey].items.push(item);
-            }
-        }
+    pub fn monkey_business(&self) -> u64 {
+        let mut item_counts = self.item_counts.clone();
+        item_counts.sort_by(|a, b| b.cmp(a));
+        item_counts.iter().take(2).product::<u64>()
     }
+}
+
+pub fn process_part1(input: &str) -> String {
+    let mut simulation = Simulation::new(input, true);
+    for _ in 0..20 {
+        simulation.round();
+    }
+    simulation.monkey_business().to_string()
+}
 
-    item_counts.sort_by(|a, b| b.cmp(a));
-    item_counts.iter().take(2).product::<u64>().to_string()
+pub fn process_part2(input: &str) -> String {
+    let mut simulation = Simulation::new(input, false);
+    for _ in 0..10000 {
+        simulation.round();
+    }
+    simulation.monkey_business().to_string()
 }
 
 #[cfg(test)]
@@ -196,4 +226,21 @@ Monkey 3:
         let result = process_part2(INPUT);
         assert_eq!(result, "2713310158");
     }
+
+    #[test]
+    fn single_round() {
+        let mut simulation = Simulation::new(INPUT, true);
+        simulation.round();
+        assert_eq!(simulation.rounds(), 1);
+        assert_eq!(
+            simulation.items(),
+            [
+                &[20, 23, 27, 26][..],
+                &[2080, 25, 167, 207, 401, 1046],
+                &[],
+                &[],
+            ]
+        );
+        assert_eq!(simulation.inspections(), [2, 4, 3, 5]);
+    }
 }
@@ -75,122 +75,111 @@ fn shortest_path(
     None
 }
 
-pub fn process_part1(input: &str) -> String {
-    let mut grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-
-    let mut adj_list: HashMap<Position, Vec<Edge>> = HashMap::new();
-    let (mut start, mut end) = (Position(0, 0), Position(0, 0));
+pub struct HeightMap {
+    adj_list: HashMap<Position, Vec<Edge>>,
+    start: Position,
+    end: Position,
+    lowest_positions: Vec<Position>,
+}
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == 'S' {
-                start = Position(i, j);
-                grid[i][j] = 'a';
-            }
-            if grid[i][j] == 'E' {
-                end = Position(i, j);
-                grid[i][j] = 'z';
+impl HeightMap {
+    pub fn new(input: &str) -> Self {
+        let mut grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+
+        let mut adj_list: HashMap<Position, Vec<Edge>> = HashMap::new();
+        let (mut start, mut end) = (Position(0, 0), Position(0, 0));
+
+        for i in 0..grid.len() {
+            for j in 0..grid[i].len() {
+                if grid[i][j] == 'S' {
+                    start = Position(i, j);
+                    grid[i][j] = 'a';
+                }
+                if grid[i][j] == 'E' {
+                    end = Position(i, j);
+                    grid[i][j] = 'z';
+                }
             }
         }
-    }
 
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            let position = Position(i, j);
-            let value = grid[i][j];
-            adj_list.insert(position, vec![]);
-
-            if i != 0 && value as i8 - grid[i - 1][j] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i - 1, j),
-                    cost: 1,
-                });
-            }
-
-            if j != 0 && value as i8 - grid[i][j - 1] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i, j - 1),
-                    cost: 1,
-                });
-            }
-
-            if i != grid.len() - 1 && value as i8 - grid[i + 1][j] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i + 1, j),
-                    cost: 1,
-                });
-            }
-            if j != grid[i].len() - 1 && value as i8 - grid[i][j + 1] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i, j + 1),
-                    cost: 1,
-                });
+        let mut lowest_positions = vec![];
+
+        for i in 0..grid.len() {
+            for j in 0..grid[i].len() {
+                let position = Position(i, j);
+                let value = grid[i][j];
+                adj_list.insert(position, vec![]);
+
+                if value == 'a' {
+                    lowest_positions.push(position);
+                }
+
+                if i != 0 && value as i8 - grid[i - 1][j] as i8 >= -1 {
+                    adj_list.get_mut(&position).unwrap().push(Edge {
+                        node: Position(i - 1, j),
+                        cost: 1,
+                    });
+                }
+
+                if j != 0 && value as i8 - grid[i][j - 1] as i8 >= -1 {
+                    adj_list.get_mut(&position).unwrap().push(Edge {
+                        node: Position(i, j - 1),
+                        cost: 1,
+                    });
+                }
+
+                if i != grid.len() - 1 && value as i8 - grid[i + 1][j] as i8 >= -1 {
+                    adj_list.get_mut(&position).unwrap().push(Edge {
+                        node: Position(i + 1, j),
+                        cost: 1,
+                    });
+                }
+                if j != grid[i].len() - 1 && value as i8 - grid[i][j + 1] as i8 >= -1 {
+                    adj_list.get_mut(&position).unwrap().push(Edge {
+                        node: Position(i, j + 1),
+                        cost: 1,
+                    });
+                }
             }
         }
-    }
-
-    shortest_path(&adj_list, start, end).unwrap().to_string()
-}
 
-pub fn process_part2(input: &str) -> String {
-    let mut grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-
-    let mut adj_list: HashMap<Position, Vec<Edge>> = HashMap::new();
-    let mut end = Position(0, 0);
-
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            if grid[i][j] == 'E' {
-                end = Position(i, j);
-                grid[i][j] = 'z';
-            }
+        HeightMap {
+            adj_list,
+            start,
+            end,
+            lowest_positions,
         }
     }
 
-    let mut lowest_positions = vec![];
-
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            let position = Position(i, j);
-            let value = grid[i][j];
-            adj_list.insert(position, vec![]);
-
-            if value == 'a' {
-                lowest_positions.push(position);
-            }
-
-            if i != 0 && value as i8 - grid[i - 1][j] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i - 1, j),
-                    cost: 1,
-                });
-            }
+    pub fn start(&self) -> (usize, usize) {
+        (self.start.0, self.start.1)
+    }
 
-            if j != 0 && value as i8 - grid[i][j - 1] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i, j - 1),
-                    cost: 1,
-                });
-            }
+    pub fn end(&self) -> (usize, usize) {
+        (self.end.0, self.end.1)
+    }
 
-            if i != grid.len() - 1 && value as i8 - grid[i + 1][j] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i + 1, j),
-                    cost: 1,
-                });
-            }
-            if j != grid[i].len() - 1 && value as i8 - grid[i][j + 1] as i8 >= -1 {
-                adj_list.get_mut(&position).unwrap().push(Edge {
-                    node: Position(i, j + 1),
-                    cost: 1,
-                });
-            }
+    // Fewest steps from the given (row, column) cell to the best signal,
+    // `None` when it is off the map or cannot reach it.
+    pub fn distance(&self, from: (usize, usize)) -> Option<usize> {
+        let from = Position(from.0, from.1);
+        if !self.adj_list.contains_key(&from) {
+            return None;
         }
+        shortest_path(&self.adj_list, from, self.end)
     }
+}
+
+pub fn process_part1(input: &str) -> String {
+    let map = HeightMap::new(input);
+    map.distance(map.start()).unwrap().to_string()
+}
 
-    lowest_positions
+pub fn process_part2(input: &str) -> String {
+    let map = HeightMap::new(input);
+    map.lowest_positions
         .iter()
-        .filter_map(|start| shortest_path(&adj_list, *start, end))
+        .filter_map(|start| shortest_path(&map.adj_list, *start, map.end))
         .min()
         .unwrap()
         .to_string()
@@ -217,4 +206,14 @@ abdefghi";
         let result = process_part2(INPUT);
         assert_eq!(result, "29");
     }
+
+    #[test]
+    fn distance_from_cell() {
+        let map = HeightMap::new(INPUT);
+        assert_eq!(map.start(), (0, 0));
+        assert_eq!(map.end(), (2, 5));
+        assert_eq!(map.distance((4, 0)), Some(29));
+        assert_eq!(map.distance((2, 5)), Some(0));
+        assert_eq!(map.distance((9, 9)), None);
+    }
 }
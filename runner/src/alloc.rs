@@ -0,0 +1,197 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{catch_panic, render_table, run_part, Day};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// Forwards to the system allocator and keeps running totals. It only counts
+// once installed with `#[global_allocator]`, which the `profile` binary does.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+// Peak is reported relative to what was already live when `f` started, so it
+// is the extra heap the measured code needed at its worst moment.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let (allocations, bytes) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        BYTES.load(Ordering::Relaxed),
+    );
+
+    let result = f();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+    };
+    (result, stats)
+}
+
+pub struct PartProfile {
+    pub day: u8,
+    pub part: usize,
+    pub parse: Option<AllocStats>,
+    pub total: Result<AllocStats, String>,
+}
+
+impl PartProfile {
+    // Solvers parse and solve in one call, so the solve share is whatever the
+    // whole part allocated on top of a standalone parse.
+    pub fn solve(&self) -> Option<AllocStats> {
+        let total = self.total.as_ref().ok()?;
+        let parse = self.parse.unwrap_or_default();
+        Some(AllocStats {
+            allocations: total.allocations.saturating_sub(parse.allocations),
+            bytes: total.bytes.saturating_sub(parse.bytes),
+            peak: total.peak,
+        })
+    }
+}
+
+pub fn profile_day(day: &Day, input: &str) -> Vec<PartProfile> {
+    // a parser that panics on the input is still measured up to the panic
+    let parse = day
+        .parse
+        .map(|parse| measure(|| catch_panic(|| parse(input))).1);
+    [1, 2]
+        .map(|part| PartProfile {
+            day: day.number,
+            part,
+            parse,
+            total: {
+                let (outcome, stats) = measure(|| run_part(day.part(part), input));
+                outcome.answer.map(|_| stats)
+            },
+        })
+        .into()
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub fn render_profiles(profiles: &[PartProfile]) -> String {
+    let rows = profiles
+        .iter()
+        .map(|profile| {
+            let mut row = vec![format!("{:02}", profile.day), profile.part.to_string()];
+            match profile.parse {
+                Some(parse) => row.extend([
+                    parse.allocations.to_string(),
+                    format_bytes(parse.bytes),
+                    format_bytes(parse.peak),
+                ]),
+                None => row.extend(["-", "-", "-"].map(String::from)),
+            }
+            match &profile.total {
+                Ok(_) => {
+                    let solve = profile.solve().unwrap();
+                    row.extend([
+                        solve.allocations.to_string(),
+                        format_bytes(solve.bytes),
+                        format_bytes(solve.peak),
+                    ])
+                }
+                Err(message) => row.push(format!("error: {}", message)),
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    render_table(
+        &[
+            "day",
+            "part",
+            "parse allocs",
+            "parse bytes",
+            "parse peak",
+            "solve allocs",
+            "solve bytes",
+            "part peak",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn measure_counts_allocations() {
+        let (_, stats) = measure(|| {
+            let first = vec![0u8; 4096];
+            drop(first);
+            vec![0u8; 1024]
+        });
+        // Tests run in parallel and share the counters, so only the totals
+        // that never go down can be checked, and only as lower bounds.
+        assert!(stats.allocations >= 2);
+        assert!(stats.bytes >= 5120);
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
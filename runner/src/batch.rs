@@ -0,0 +1,243 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use crate::{format_duration, render_table, run_part, Day, Outcome};
+
+// Inputs are `<name>.txt`; the answers they are expected to produce sit
+// beside them as `<name>.part-1.expected` and `<name>.part-2.expected`.
+pub struct InputFile {
+    pub name: String,
+    // a file that cannot be read becomes an error in its row rather than
+    // stopping the batch
+    pub input: Result<String, String>,
+    pub expected: [Option<String>; 2],
+}
+
+pub fn collect_inputs(dir: &Path) -> io::Result<Vec<InputFile>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    let mut inputs = vec![];
+    for path in paths {
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("txt") {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let expected = [1, 2].map(|part| {
+            fs::read_to_string(dir.join(format!("{}.part-{}.expected", name, part))).ok()
+        });
+        inputs.push(InputFile {
+            input: fs::read_to_string(&path).map_err(|err| err.to_string()),
+            name,
+            expected,
+        });
+    }
+    Ok(inputs)
+}
+
+pub enum Verdict {
+    Unchecked,
+    Pass,
+    Fail(String),
+}
+
+pub struct Cell {
+    pub outcome: Outcome,
+    pub verdict: Verdict,
+}
+
+impl Cell {
+    fn new(outcome: Outcome, expected: Option<&String>) -> Self {
+        let verdict = match (&outcome.answer, expected) {
+            (Ok(answer), Some(expected)) if answer.trim_end() == expected.trim_end() => {
+                Verdict::Pass
+            }
+            (Ok(_), Some(expected)) => Verdict::Fail(expected.trim_end().to_string()),
+            _ => Verdict::Unchecked,
+        };
+        Cell { outcome, verdict }
+    }
+
+    fn render(&self) -> String {
+        let answer = match &self.outcome.answer {
+            Ok(answer) => answer,
+            Err(message) => return format!("error: {}", message),
+        };
+        let mut text = format!(
+            "{} ({})",
+            short_answer(answer),
+            format_duration(self.outcome.elapsed)
+        );
+        match &self.verdict {
+            Verdict::Unchecked => {}
+            Verdict::Pass => text.push_str(" ok"),
+            Verdict::Fail(expected) => {
+                text.push_str(&format!(" MISMATCH, expected {}", short_answer(expected)))
+            }
+        }
+        text
+    }
+}
+
+// Rendered answers such as the day-10 CRT span several lines and would break
+// the table apart, so they are only summarised here.
+fn short_answer(answer: &str) -> String {
+    match answer.lines().count() {
+        0 | 1 => answer.to_string(),
+        n => format!("<{} lines>", n),
+    }
+}
+
+pub struct Row {
+    pub day: u8,
+    pub name: String,
+    pub cells: [Cell; 2],
+}
+
+pub fn run_batch(day: &Day, inputs: &[InputFile]) -> Vec<Row> {
+    inputs
+        .iter()
+        .map(|input| Row {
+            day: day.number,
+            name: input.name.clone(),
+            cells: [1, 2].map(|part| {
+                let outcome = match &input.input {
+                    Ok(text) => run_part(day.part(part), text),
+                    Err(message) => Outcome {
+                        answer: Err(message.clone()),
+                        elapsed: Duration::ZERO,
+                    },
+                };
+                Cell::new(outcome, input.expected[part - 1].as_ref())
+            }),
+        })
+        .collect()
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub unchecked: usize,
+}
+
+impl Summary {
+    pub fn from_rows(rows: &[Row]) -> Self {
+        let mut summary = Summary::default();
+        for cell in rows.iter().flat_map(|row| row.cells.iter()) {
+            match (&cell.outcome.answer, &cell.verdict) {
+                (Err(_), _) => summary.errors += 1,
+                (_, Verdict::Pass) => summary.passed += 1,
+                (_, Verdict::Fail(_)) => summary.failed += 1,
+                (_, Verdict::Unchecked) => summary.unchecked += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && self.errors == 0
+    }
+}
+
+pub fn render_matrix(rows: &[Row]) -> String {
+    let cells = rows
+        .iter()
+        .map(|row| {
+            vec![
+                format!("{:02}", row.day),
+                row.name.clone(),
+                row.cells[0].render(),
+                row.cells[1].render(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let summary = Summary::from_rows(rows);
+    format!(
+        "{}\n\n{} passed, {} mismatched, {} errors, {} unchecked",
+        render_table(&["day", "input", "part 1", "part 2"], &cells),
+        summary.passed,
+        summary.failed,
+        summary.errors,
+        summary.unchecked
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_day, silence_panics};
+
+    fn input(name: &str, input: &str, expected: [Option<&str>; 2]) -> InputFile {
+        InputFile {
+            name: name.to_string(),
+            input: Ok(input.to_string()),
+            expected: expected.map(|e| e.map(|e| e.to_string())),
+        }
+    }
+
+    #[test]
+    fn batch_keeps_going_after_a_failure() {
+        silence_panics();
+        let inputs = [
+            input("broken", "not a number", [None, None]),
+            input(
+                "alice",
+                "1000\n\n2000\n3000\n",
+                [Some("5000\n"), Some("7000")],
+            ),
+        ];
+        let rows = run_batch(find_day(1).unwrap(), &inputs);
+
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].cells[0].outcome.answer.is_err());
+        assert_eq!(rows[1].cells[0].outcome.answer, Ok("5000".to_string()));
+        assert_eq!(
+            Summary::from_rows(&rows),
+            Summary {
+                passed: 1,
+                failed: 1,
+                errors: 2,
+                unchecked: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn collect_inputs_pairs_expected_answers() {
+        let dir = std::env::temp_dir().join(format!("runner-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("bob.txt"), "1").unwrap();
+        fs::write(dir.join("bob.part-2.expected"), "1").unwrap();
+        fs::write(dir.join("alice.txt"), "2").unwrap();
+        fs::write(dir.join("notes.md"), "ignored").unwrap();
+
+        let inputs = collect_inputs(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            inputs.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(),
+            ["alice", "bob"]
+        );
+        assert_eq!(inputs[1].expected, [None, Some("1".to_string())]);
+    }
+
+    #[test]
+    fn unreadable_input_is_reported_in_its_row() {
+        let dir = std::env::temp_dir().join(format!("runner-batch-utf8-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("alice.txt"), "1000\n\n2000\n").unwrap();
+        fs::write(dir.join("bob.txt"), [0xff, 0xfe, b'1']).unwrap();
+
+        let inputs = collect_inputs(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let rows = run_batch(find_day(1).unwrap(), &inputs);
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].cells[0].outcome.answer, Ok("2000".to_string()));
+        assert!(rows[1].cells[0].outcome.answer.is_err());
+        assert!(rows[1].cells[1].render().starts_with("error: "));
+    }
+}
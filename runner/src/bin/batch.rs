@@ -0,0 +1,51 @@
+use runner::{
+    batch::{collect_inputs, render_matrix, run_batch, Summary},
+    find_day, silence_panics, DAYS,
+};
+use std::{env, path::PathBuf, process};
+
+const USAGE: &str = "usage: batch [--day N] <dir>
+
+Without --day, <dir> holds one `day-NN` folder of inputs per day.
+With --day, <dir> holds the inputs for that day directly.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (day, dir) = match args.as_slice() {
+        [flag, day, dir] if flag == "--day" => match day.parse().ok().and_then(find_day) {
+            Some(day) => (Some(day), PathBuf::from(dir)),
+            None => {
+                eprintln!("Unknown day {}", day);
+                process::exit(2);
+            }
+        },
+        [dir] => (None, PathBuf::from(dir)),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let folders = match day {
+        Some(day) => vec![(day, dir)],
+        None => DAYS
+            .iter()
+            .map(|day| (day, dir.join(day.name())))
+            .filter(|(_, folder)| folder.is_dir())
+            .collect(),
+    };
+
+    silence_panics();
+    let mut rows = vec![];
+    for (day, folder) in folders {
+        match collect_inputs(&folder) {
+            Ok(inputs) => rows.extend(run_batch(day, &inputs)),
+            Err(err) => eprintln!("Skipping {}: {}", folder.display(), err),
+        }
+    }
+
+    println!("{}", render_matrix(&rows));
+    if !Summary::from_rows(&rows).is_success() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,60 @@
+use runner::{
+    alloc::{profile_day, render_profiles, CountingAllocator},
+    find_day, silence_panics,
+    status::repository_root,
+    DAYS,
+};
+use std::{env, fs, process};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const USAGE: &str = "usage: profile [--day N] [--sort allocs|bytes|peak]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (mut days, mut sort) = (DAYS.iter().collect::<Vec<_>>(), None);
+    for pair in args.chunks(2) {
+        match pair {
+            [flag, day] if flag == "--day" => match day.parse().ok().and_then(find_day) {
+                Some(day) => days = vec![day],
+                None => {
+                    eprintln!("Unknown day {}", day);
+                    process::exit(2);
+                }
+            },
+            [flag, key]
+                if flag == "--sort" && ["allocs", "bytes", "peak"].contains(&key.as_str()) =>
+            {
+                sort = Some(key.clone())
+            }
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    silence_panics();
+    let mut profiles = vec![];
+    for day in days {
+        let path = repository_root().join(day.name()).join("input.txt");
+        match fs::read_to_string(&path) {
+            Ok(input) => profiles.extend(profile_day(day, &input)),
+            Err(err) => eprintln!("Skipping {}: {}", path.display(), err),
+        }
+    }
+
+    // sort on the solve columns the table shows, not the parse-inclusive totals
+    if let Some(key) = sort {
+        profiles.sort_by_key(|profile| {
+            let solve = profile.solve().unwrap_or_default();
+            std::cmp::Reverse(match key.as_str() {
+                "allocs" => solve.allocations,
+                "bytes" => solve.bytes,
+                _ => solve.peak,
+            })
+        });
+    }
+    println!("{}", render_profiles(&profiles));
+}
@@ -0,0 +1,68 @@
+use runner::{
+    find_day,
+    repl::{Reply, Session},
+    silence_panics,
+    status::repository_root,
+};
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process,
+};
+
+const USAGE: &str = "usage: repl <day> [input]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (day, path) = match args.as_slice() {
+        [day] | [day, _] => match day.parse().ok().and_then(find_day) {
+            Some(found) => (
+                found,
+                args.get(1)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| repository_root().join(found.name()).join("input.txt")),
+            ),
+            None => {
+                eprintln!("Unknown day {}", day);
+                process::exit(2);
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Could not read {}: {}", path.display(), err);
+        process::exit(1);
+    });
+
+    silence_panics();
+    let mut session = Session::new(day, input).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    println!(
+        "{} loaded from {}, type help for commands",
+        day.name(),
+        path.display()
+    );
+
+    let stdin = io::stdin();
+    loop {
+        print!("{}> ", day.name());
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        match session.execute(&line) {
+            Reply::Output(Ok(output)) if output.is_empty() => {}
+            Reply::Output(Ok(output)) => println!("{}", output),
+            Reply::Output(Err(err)) => println!("error: {}", err),
+            Reply::Quit => break,
+        }
+    }
+}
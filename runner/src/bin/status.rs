@@ -0,0 +1,29 @@
+use runner::{
+    silence_panics,
+    status::{day_dirs, inspect, render_status, repository_root},
+};
+use std::{env, process};
+
+const USAGE: &str = "usage: status [--markdown] [--skip-tests]";
+
+fn main() {
+    let (mut markdown, mut run_tests) = (false, true);
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--markdown" => markdown = true,
+            "--skip-tests" => run_tests = false,
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    silence_panics();
+    let statuses = day_dirs(&repository_root())
+        .unwrap()
+        .iter()
+        .map(|dir| inspect(dir, run_tests))
+        .collect::<Vec<_>>();
+    println!("{}", render_status(&statuses, markdown));
+}
@@ -0,0 +1,254 @@
+use std::{
+    panic,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "alloc-profile")]
+pub mod alloc;
+pub mod batch;
+pub mod repl;
+pub mod status;
+
+pub struct Day {
+    pub number: u8,
+    pub part1: fn(&str) -> String,
+    pub part2: fn(&str) -> String,
+    // Runs just the parsing stage, for days whose parser is exposed on its own.
+    pub parse: Option<fn(&str)>,
+}
+
+impl Day {
+    pub fn name(&self) -> String {
+        format!("day-{:02}", self.number)
+    }
+
+    pub fn part(&self, part: usize) -> fn(&str) -> String {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => panic!("There are only two parts, got {}", part),
+        }
+    }
+}
+
+pub const DAYS: [Day; 12] = [
+    Day {
+        number: 1,
+        part1: day_01::process_part1,
+        part2: day_01::process_part2,
+        parse: Some(|input| drop(day_01::parse_elves(input).collect::<Vec<_>>())),
+    },
+    Day {
+        number: 2,
+        part1: day_02::process_part1,
+        part2: day_02::process_part2,
+        parse: Some(|input| {
+            let game = day_02::Game::default();
+            let _ = input
+                .lines()
+                .map(|line| day_02::Round::parse(line, &game, &day_02::AsMove))
+                .collect::<Result<Vec<_>, _>>();
+        }),
+    },
+    Day {
+        number: 3,
+        part1: day_03::process_part1,
+        part2: day_03::process_part2,
+        parse: Some(|input| {
+            let _ = input
+                .lines()
+                .map(str::parse::<day_03::ItemSet>)
+                .collect::<Result<Vec<_>, _>>();
+        }),
+    },
+    Day {
+        number: 4,
+        part1: day_04::process_part1,
+        part2: day_04::process_part2,
+        parse: Some(|input| drop(day_04::parse_assignments(input))),
+    },
+    Day {
+        number: 5,
+        part1: day_05::process_part1,
+        part2: day_05::process_part2,
+        parse: Some(|input| {
+            let _ = day_05::parse_input(input);
+        }),
+    },
+    Day {
+        number: 6,
+        part1: day_06::process_part1,
+        part2: day_06::process_part2,
+        parse: None,
+    },
+    Day {
+        number: 7,
+        part1: day_07::process_part1,
+        part2: day_07::process_part2,
+        parse: Some(|input| drop(day_07::parse_commands(input))),
+    },
+    Day {
+        number: 8,
+        part1: day_08::process_part1,
+        part2: day_08::process_part2,
+        parse: None,
+    },
+    Day {
+        number: 9,
+        part1: day_09::process_part1,
+        part2: day_09::process_part2,
+        parse: Some(|input| drop(day_09::parse_moves(input))),
+    },
+    Day {
+        number: 10,
+        part1: day_10::process_part1,
+        part2: day_10::process_part2,
+        parse: Some(|input| drop(day_10::parse_operations(input))),
+    },
+    Day {
+        number: 11,
+        part1: day_11::process_part1,
+        part2: day_11::process_part2,
+        parse: Some(|input| drop(day_11::parse_monkeys(input))),
+    },
+    Day {
+        number: 12,
+        part1: day_12::process_part1,
+        part2: day_12::process_part2,
+        parse: Some(|input| drop(day_12::HeightMap::new(input))),
+    },
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+pub struct Outcome {
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+// Solvers unwrap freely, so a panic is how a bad input shows up. Catching it
+// keeps one broken input from taking the rest of a run down with it.
+pub fn catch_panic<T>(f: impl FnOnce() -> T + panic::UnwindSafe) -> Result<T, String> {
+    panic::catch_unwind(f).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "solver panicked".to_string()
+        }
+    })
+}
+
+pub fn run_part(solver: fn(&str) -> String, input: &str) -> Outcome {
+    let start = Instant::now();
+    let answer = catch_panic(|| solver(input));
+    Outcome {
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
+
+// The default hook prints every caught panic to stderr, which would tear
+// through the middle of a rendered table.
+pub fn silence_panics() {
+    panic::set_hook(Box::new(|_| {}));
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", micros as f64 / 1000.0)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+pub fn render_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(header.to_vec())];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-"),
+    );
+    for row in rows {
+        lines.push(line(row.iter().map(|cell| cell.as_str()).collect()));
+    }
+    lines.join("\n")
+}
+
+pub fn render_markdown(header: &[&str], rows: &[Vec<String>]) -> String {
+    let line = |cells: Vec<&str>| format!("| {} |", cells.join(" | "));
+
+    let mut lines = vec![line(header.to_vec())];
+    lines.push(line(header.iter().map(|_| "---").collect()));
+    for row in rows {
+        lines.push(line(row.iter().map(|cell| cell.as_str()).collect()));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broken(_input: &str) -> String {
+        panic!("bad input")
+    }
+
+    #[test]
+    fn run_part_catches_panics() {
+        silence_panics();
+        let outcome = run_part(broken, "");
+        assert_eq!(outcome.answer, Err("bad input".to_string()));
+
+        let outcome = run_part(DAYS[0].part1, "1\n2\n\n4");
+        assert_eq!(outcome.answer, Ok("4".to_string()));
+    }
+
+    #[test]
+    fn table() {
+        let rows = vec![
+            vec!["1".to_string(), "alice".to_string()],
+            vec!["12".to_string(), "bob".to_string()],
+        ];
+        assert_eq!(
+            render_table(&["day", "input"], &rows),
+            "day | input
+----+------
+1   | alice
+12  | bob"
+        );
+        assert_eq!(
+            render_markdown(&["day", "input"], &rows),
+            "| day | input |
+| --- | --- |
+| 1 | alice |
+| 12 | bob |"
+        );
+    }
+}
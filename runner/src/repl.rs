@@ -0,0 +1,312 @@
+use crate::{catch_panic, run_part, Day};
+
+// Day-specific commands over a parsed model. `run` returns `None` for
+// commands the model does not know, so the session can report them.
+pub trait Model {
+    fn commands(&self) -> Vec<(&'static str, &'static str)>;
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>>;
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[&str], idx: usize, name: &str) -> Result<T, String> {
+    let arg = args
+        .get(idx)
+        .ok_or_else(|| format!("Missing argument <{}>", name))?;
+    arg.parse()
+        .map_err(|_| format!("Invalid <{}>, got {}", name, arg))
+}
+
+struct Directories {
+    dirs: Vec<day_07::DirSize>,
+}
+
+impl Model for Directories {
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "dirs [min-size]",
+                "directories of at least min-size, largest first",
+            ),
+            ("size <path>", "total size of one directory"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        let result = match command {
+            "dirs" => {
+                let min_size = if args.is_empty() {
+                    Ok(0)
+                } else {
+                    parse_arg(args, 0, "min-size")
+                };
+                min_size.map(|min_size| {
+                    let mut dirs = self
+                        .dirs
+                        .iter()
+                        .filter(|dir| dir.size >= min_size)
+                        .collect::<Vec<_>>();
+                    dirs.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+                    dirs.iter()
+                        .map(|dir| format!("{:>10} {}", dir.size, dir.path))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+            }
+            "size" => parse_arg::<String>(args, 0, "path").and_then(|path| {
+                self.dirs
+                    .iter()
+                    .find(|dir| dir.path == path)
+                    .map(|dir| dir.size.to_string())
+                    .ok_or_else(|| format!("No directory {}", path))
+            }),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+struct Monkeys {
+    input: String,
+    simulation: day_11::Simulation,
+}
+
+impl Monkeys {
+    fn state(&self) -> String {
+        let items = self.simulation.items();
+        let inspections = self.simulation.inspections();
+        let mut lines = vec![format!("after round {}", self.simulation.rounds())];
+        for (i, (items, inspected)) in items.iter().zip(inspections).enumerate() {
+            lines.push(format!(
+                "monkey {}: inspected {:>6}, holding {:?}",
+                i, inspected, items
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl Model for Monkeys {
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "round [n]",
+                "play n more rounds (default 1) and show the monkeys",
+            ),
+            ("monkeys", "show held items and inspection counts"),
+            ("business", "product of the two highest inspection counts"),
+            (
+                "reset [relief|worry]",
+                "start over, with or without the relief step",
+            ),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        let result = match command {
+            "round" => {
+                let rounds = if args.is_empty() {
+                    Ok(1)
+                } else {
+                    parse_arg(args, 0, "n")
+                };
+                rounds.map(|rounds: usize| {
+                    for _ in 0..rounds {
+                        self.simulation.round();
+                    }
+                    self.state()
+                })
+            }
+            "monkeys" => Ok(self.state()),
+            "business" => Ok(self.simulation.monkey_business().to_string()),
+            "reset" => match args.first().copied().unwrap_or("relief") {
+                mode @ ("relief" | "worry") => {
+                    self.simulation = day_11::Simulation::new(&self.input, mode == "relief");
+                    Ok(self.state())
+                }
+                other => Err(format!("Unknown mode {}", other)),
+            },
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+struct Heights {
+    map: day_12::HeightMap,
+}
+
+impl Model for Heights {
+    fn commands(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "distance <row> <col>",
+                "fewest steps from a cell to the signal",
+            ),
+            ("start", "position of S"),
+            ("end", "position of E"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Option<Result<String, String>> {
+        let result = match command {
+            "distance" => parse_arg(args, 0, "row")
+                .and_then(|row| Ok((row, parse_arg(args, 1, "col")?)))
+                .and_then(|cell| {
+                    self.map
+                        .distance(cell)
+                        .map(|steps| steps.to_string())
+                        .ok_or_else(|| format!("No path from {:?}", cell))
+                }),
+            "start" => Ok(format!("{:?}", self.map.start())),
+            "end" => Ok(format!("{:?}", self.map.end())),
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+// Only days with something worth exploring beyond the answers get a model.
+pub fn load_model(day: u8, input: &str) -> Option<Box<dyn Model>> {
+    match day {
+        7 => Some(Box::new(Directories {
+            dirs: day_07::directory_sizes(input),
+        })),
+        11 => Some(Box::new(Monkeys {
+            input: input.to_string(),
+            simulation: day_11::Simulation::new(input, true),
+        })),
+        12 => Some(Box::new(Heights {
+            map: day_12::HeightMap::new(input),
+        })),
+        _ => None,
+    }
+}
+
+pub struct Session {
+    day: &'static Day,
+    input: String,
+    model: Option<Box<dyn Model>>,
+    history: Vec<String>,
+}
+
+pub enum Reply {
+    Output(Result<String, String>),
+    Quit,
+}
+
+impl Session {
+    pub fn new(day: &'static Day, input: String) -> Result<Self, String> {
+        let model = catch_panic(|| load_model(day.number, &input))
+            .map_err(|message| format!("Could not parse the input: {}", message))?;
+        Ok(Session {
+            day,
+            input,
+            model,
+            history: vec![],
+        })
+    }
+
+    pub fn help(&self) -> String {
+        let mut commands = vec![
+            ("part1", "answer for part 1"),
+            ("part2", "answer for part 2"),
+            ("history", "list previous commands"),
+            ("!<n>", "run command <n> from the history again"),
+            ("help", "show this list"),
+            ("quit", "leave the REPL"),
+        ];
+        if let Some(model) = &self.model {
+            commands.extend(model.commands());
+        }
+        let width = commands.iter().map(|(usage, _)| usage.len()).max().unwrap();
+        commands
+            .iter()
+            .map(|(usage, help)| format!("{:<width$}  {}", usage, help, width = width))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn execute(&mut self, line: &str) -> Reply {
+        let line = line.trim();
+        let line = match line.strip_prefix('!') {
+            Some(n) => match n
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| self.history.get(n.checked_sub(1)?))
+            {
+                Some(previous) => previous.clone(),
+                None => return Reply::Output(Err(format!("No history entry {}", n))),
+            },
+            None => line.to_string(),
+        };
+        if line.is_empty() {
+            return Reply::Output(Ok(String::new()));
+        }
+        self.history.push(line.clone());
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let (command, args) = (words[0], &words[1..]);
+        let result = match command {
+            "quit" | "exit" => return Reply::Quit,
+            "help" => Ok(self.help()),
+            "history" => Ok(self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:>4}  {}", i + 1, line))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "part1" | "part2" => {
+                let part = if command == "part1" { 1 } else { 2 };
+                run_part(self.day.part(part), &self.input).answer
+            }
+            _ => self
+                .model
+                .as_mut()
+                .and_then(|model| model.run(command, args))
+                .unwrap_or_else(|| Err(format!("Unknown command {}, try help", command))),
+        };
+        Reply::Output(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_day;
+
+    fn output(session: &mut Session, line: &str) -> Result<String, String> {
+        match session.execute(line) {
+            Reply::Output(result) => result,
+            Reply::Quit => panic!("unexpected quit"),
+        }
+    }
+
+    const DAY_12: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+    #[test]
+    fn answers_and_history() {
+        let mut session = Session::new(find_day(12).unwrap(), DAY_12.to_string()).unwrap();
+        assert_eq!(output(&mut session, "part1"), Ok("31".to_string()));
+        assert_eq!(output(&mut session, "distance 4 0"), Ok("29".to_string()));
+        assert_eq!(output(&mut session, "!2"), Ok("29".to_string()));
+        assert_eq!(
+            output(&mut session, "history"),
+            Ok("   1  part1\n   2  distance 4 0\n   3  distance 4 0\n   4  history".to_string())
+        );
+        assert!(output(&mut session, "distance x 0").is_err());
+        assert!(output(&mut session, "fly").is_err());
+        assert!(output(&mut session, "!0").is_err());
+        assert!(matches!(session.execute("quit"), Reply::Quit));
+    }
+
+    #[test]
+    fn days_without_a_model_still_answer() {
+        let mut session = Session::new(find_day(1).unwrap(), "1\n\n2".to_string()).unwrap();
+        assert_eq!(output(&mut session, "part2"), Ok("3".to_string()));
+        assert!(output(&mut session, "dirs").is_err());
+    }
+}
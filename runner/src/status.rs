@@ -0,0 +1,226 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use crate::{find_day, format_duration, render_markdown, render_table, run_part};
+
+pub fn repository_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+// A part counts as implemented once its body is no longer the placeholder
+// that `new-day.sh` copies over from `base`.
+pub fn is_implemented(source: &str, function: &str) -> bool {
+    let Some(start) = source.find(&format!("fn {}(", function)) else {
+        return false;
+    };
+    let body = &source[start..];
+    let body = match body.find('{') {
+        Some(open) => &body[open + 1..],
+        None => return false,
+    };
+    let body = &body[..body.find("\n}").unwrap_or(body.len())];
+    body.trim() != "\"result\".to_string()"
+}
+
+pub fn count_tests(source: &str) -> usize {
+    source.matches("#[test]").count()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Tests {
+    None,
+    NotRun(usize),
+    Passed(usize),
+    Failed(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Answers {
+    NotLinked,
+    NoInput,
+    NoneRecorded,
+    Verified,
+    Mismatch(Vec<usize>),
+    Error(Vec<usize>),
+}
+
+pub struct DayStatus {
+    pub name: String,
+    pub parts: [bool; 2],
+    pub tests: Tests,
+    pub has_input: bool,
+    pub answers: Answers,
+    pub runtime: Option<Duration>,
+}
+
+pub fn day_dirs(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut dirs = fs::read_dir(root)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("day-"))
+        })
+        .collect::<Vec<_>>();
+    dirs.sort();
+    Ok(dirs)
+}
+
+pub fn inspect(dir: &Path, run_tests: bool) -> DayStatus {
+    let name = dir.file_name().unwrap().to_string_lossy().to_string();
+    let source = fs::read_to_string(dir.join("src/lib.rs")).unwrap_or_default();
+    let parts = ["process_part1", "process_part2"].map(|f| is_implemented(&source, f));
+
+    let tests = match count_tests(&source) {
+        0 => Tests::None,
+        n if !run_tests => Tests::NotRun(n),
+        n => {
+            let passed = Command::new("cargo")
+                .args(["test", "--quiet"])
+                .current_dir(dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+            if passed {
+                Tests::Passed(n)
+            } else {
+                Tests::Failed(n)
+            }
+        }
+    };
+
+    let input = fs::read_to_string(dir.join("input.txt")).ok();
+    let day = name
+        .strip_prefix("day-")
+        .and_then(|number| number.parse().ok())
+        .and_then(find_day);
+    let (answers, runtime) = match (day, &input) {
+        (None, _) => (Answers::NotLinked, None),
+        (_, None) => (Answers::NoInput, None),
+        (Some(day), Some(input)) => {
+            let mut runtime = Duration::ZERO;
+            let (mut mismatched, mut errors, mut recorded) = (vec![], vec![], 0);
+            for part in [1, 2] {
+                let outcome = run_part(day.part(part), input);
+                runtime += outcome.elapsed;
+                let expected = dir.join(format!("input.part-{}.expected", part));
+                let Ok(expected) = fs::read_to_string(expected) else {
+                    continue;
+                };
+                recorded += 1;
+                match outcome.answer {
+                    Ok(answer) if answer.trim_end() == expected.trim_end() => {}
+                    Ok(_) => mismatched.push(part),
+                    Err(_) => errors.push(part),
+                }
+            }
+            let answers = if !errors.is_empty() {
+                Answers::Error(errors)
+            } else if !mismatched.is_empty() {
+                Answers::Mismatch(mismatched)
+            } else if recorded == 0 {
+                Answers::NoneRecorded
+            } else {
+                Answers::Verified
+            };
+            (answers, Some(runtime))
+        }
+    };
+
+    DayStatus {
+        name,
+        parts,
+        tests,
+        has_input: input.is_some(),
+        answers,
+        runtime,
+    }
+}
+
+fn parts_list(parts: &[usize]) -> String {
+    parts
+        .iter()
+        .map(|part| format!("part {}", part))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl DayStatus {
+    fn cells(&self) -> Vec<String> {
+        let part = |done| if done { "done" } else { "todo" }.to_string();
+        vec![
+            self.name.clone(),
+            part(self.parts[0]),
+            part(self.parts[1]),
+            match self.tests {
+                Tests::None => "none".to_string(),
+                Tests::NotRun(n) => format!("{} not run", n),
+                Tests::Passed(n) => format!("{} pass", n),
+                Tests::Failed(n) => format!("{} FAIL", n),
+            },
+            if self.has_input { "yes" } else { "no" }.to_string(),
+            match &self.answers {
+                Answers::NotLinked => "not in runner".to_string(),
+                Answers::NoInput => "-".to_string(),
+                Answers::NoneRecorded => "none recorded".to_string(),
+                Answers::Verified => "verified".to_string(),
+                Answers::Mismatch(parts) => format!("MISMATCH {}", parts_list(parts)),
+                Answers::Error(parts) => format!("ERROR {}", parts_list(parts)),
+            },
+            self.runtime.map_or("-".to_string(), format_duration),
+        ]
+    }
+}
+
+const HEADER: [&str; 7] = [
+    "day", "part 1", "part 2", "tests", "input", "answers", "runtime",
+];
+
+pub fn render_status(statuses: &[DayStatus], markdown: bool) -> String {
+    let rows = statuses.iter().map(|s| s.cells()).collect::<Vec<_>>();
+    if markdown {
+        render_markdown(&HEADER, &rows)
+    } else {
+        render_table(&HEADER, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholder_detection() {
+        let base = fs::read_to_string(repository_root().join("base/src/lib.rs")).unwrap();
+        assert!(!is_implemented(&base, "process_part1"));
+        assert!(!is_implemented(&base, "process_part2"));
+        assert_eq!(count_tests(&base), 2);
+
+        let day = fs::read_to_string(repository_root().join("day-01/src/lib.rs")).unwrap();
+        assert!(is_implemented(&day, "process_part1"));
+        assert!(is_implemented(&day, "process_part2"));
+        assert!(!is_implemented(&day, "process_part3"));
+    }
+
+    #[test]
+    fn recorded_answers_are_verified() {
+        let status = inspect(&repository_root().join("day-01"), false);
+        assert_eq!(status.parts, [true, true]);
+        assert!(matches!(status.tests, Tests::NotRun(n) if n >= 2));
+        assert!(status.has_input);
+        assert_eq!(status.answers, Answers::Verified);
+        assert!(status.runtime.is_some());
+    }
+}
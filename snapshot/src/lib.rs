@@ -0,0 +1,113 @@
+use std::{fs, path::Path};
+
+// Compares `$actual` with `snapshots/<name>.snap` in the calling crate.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        $crate::assert_snapshot(env!("CARGO_MANIFEST_DIR"), $name, &$actual)
+    };
+}
+
+// A missing or outdated snapshot never gets overwritten by a test run. The new
+// output is parked next to it as `<name>.snap.new` until it is accepted with
+// `./accept-snapshots.sh`.
+pub fn assert_snapshot(manifest_dir: &str, name: &str, actual: &str) {
+    let dir = Path::new(manifest_dir).join("snapshots");
+    let path = dir.join(format!("{}.snap", name));
+    let pending = dir.join(format!("{}.snap.new", name));
+
+    let stored = fs::read_to_string(&path).ok();
+    let expected = stored
+        .as_deref()
+        .map(|stored| stored.strip_suffix('\n').unwrap_or(stored));
+    if expected == Some(actual) {
+        let _ = fs::remove_file(&pending);
+        return;
+    }
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&pending, format!("{}\n", actual)).unwrap();
+    match expected {
+        Some(expected) => panic!(
+            "snapshot `{}` does not match, new output saved to {}\n{}",
+            name,
+            pending.display(),
+            diff(expected, actual)
+        ),
+        None => panic!(
+            "snapshot `{}` does not exist yet, new output saved to {}",
+            name,
+            pending.display()
+        ),
+    }
+}
+
+// Line diff over the longest common subsequence: lines only in the snapshot
+// are prefixed with `-`, lines only in the new output with `+`.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = vec!["--- snapshot".to_string(), "+++ actual".to_string()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!(
+            diff("##..\n#..#\n....", "##..\n#.##\n....\n####"),
+            "--- snapshot
++++ actual
+  ##..
+- #..#
++ #.##
+  ....
++ ####"
+        );
+    }
+
+    #[test]
+    fn mismatch_is_parked_for_review() {
+        let dir = std::env::temp_dir().join(format!("snapshot-{}", std::process::id()));
+        let manifest_dir = dir.to_str().unwrap();
+        fs::create_dir_all(dir.join("snapshots")).unwrap();
+        fs::write(dir.join("snapshots/crt.snap"), "#.\n.#\n").unwrap();
+
+        assert_snapshot(manifest_dir, "crt", "#.\n.#");
+        let result = std::panic::catch_unwind(|| assert_snapshot(manifest_dir, "crt", "#.\n##"));
+        let pending = fs::read_to_string(dir.join("snapshots/crt.snap.new"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(pending.unwrap(), "#.\n##\n");
+    }
+}